@@ -0,0 +1,174 @@
+//! Computer-controlled opponents. An AI player draws a hand, plays the cards
+//! it can afford and use, and leaves the rest of its turn to the rules.
+
+use bevy::prelude::*;
+use bevy_la_mesa::{
+    events::{DrawHand, PlaceCardOnTable},
+    Card, CardOnTable, Chip, ChipArea, Hand, LaMesaPluginSettings,
+};
+
+use super::{
+    cards::{
+        AdvancePhase, CardType, ChipType, GameState, Kard, PhaseTimer, PlayBlocker,
+        TransactionKind, TurnPhase,
+    },
+    territory::{PlaceInfluence, Region},
+    trade::{PendingTrade, TradeAction, TradeRequest},
+};
+use crate::screen::Pause;
+
+/// Pause between AI actions, so the table can be followed.
+const AI_THINKING_SECONDS: f32 = 0.8;
+
+/// Players controlled by the computer.
+#[derive(Resource, Default)]
+pub struct AiPlayers(pub Vec<usize>);
+
+#[derive(Resource)]
+struct AiTimer(Timer);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AiPlayers>()
+        .insert_resource(AiTimer(Timer::from_seconds(
+            AI_THINKING_SECONDS,
+            TimerMode::Once,
+        )))
+        .add_systems(
+            Update,
+            (answer_trades, play_ai_turn).run_if(in_state(Pause::Running)),
+        );
+}
+
+/// Takes gifts, turns down anything that costs the AI.
+fn answer_trades(
+    ai_players: Res<AiPlayers>,
+    pending_trade: Res<PendingTrade>,
+    mut ew_trade_request: EventWriter<TradeRequest>,
+) {
+    let Some(offer) = &pending_trade.0 else {
+        return;
+    };
+    if !ai_players.0.contains(&offer.to) {
+        return;
+    }
+
+    ew_trade_request.send(TradeRequest {
+        player: offer.to,
+        action: if offer.take.is_empty() {
+            TradeAction::Accept
+        } else {
+            TradeAction::Reject
+        },
+    });
+}
+
+/// Lower is played first; `None` means the card is no use right now.
+fn card_priority(
+    card_type: CardType,
+    chips_in_production: usize,
+    chips_in_transport: usize,
+) -> Option<usize> {
+    match card_type {
+        CardType::Production(_) => Some(0),
+        CardType::Truck | CardType::Train if chips_in_production > 0 => Some(1),
+        CardType::Export | CardType::LocalMarket if chips_in_transport > 0 => Some(1),
+        CardType::Warehouse | CardType::Bribe | CardType::Influence | CardType::Attack => Some(2),
+        _ => None,
+    }
+}
+
+fn play_ai_turn(
+    ai_players: Res<AiPlayers>,
+    mut ai_timer: ResMut<AiTimer>,
+    phase_timer: Res<PhaseTimer>,
+    time: Res<Time>,
+    mut state: ResMut<GameState>,
+    plugin_settings: Res<LaMesaPluginSettings>,
+    cards_in_hand: Query<(Entity, &Card<Kard>, &Hand)>,
+    cards_on_table: Query<&CardOnTable>,
+    chips: Query<&ChipArea, With<Chip<ChipType>>>,
+    mut ew_draw: EventWriter<DrawHand>,
+    mut ew_place_card_on_table: EventWriter<PlaceCardOnTable>,
+    mut ew_place_influence: EventWriter<PlaceInfluence>,
+    mut ew_advance_phase: EventWriter<AdvancePhase>,
+) {
+    let player = state.player;
+    if !ai_players.0.contains(&player) || state.is_bankrupt(player) {
+        return;
+    }
+
+    ai_timer.0.tick(time.delta());
+    if !ai_timer.0.finished() || !phase_timer.0.finished() {
+        return;
+    }
+
+    match state.phase {
+        TurnPhase::Prepare => {
+            ew_draw.send(DrawHand {
+                deck_marker: state.deck_marker(player),
+                num_cards: plugin_settings.hand_size,
+                player,
+            });
+            ew_advance_phase.send(AdvancePhase);
+        }
+        TurnPhase::PlaceCardsOnTable => {
+            for _ in 0..state.pending_influence[player - 1] {
+                let region = Region::ALL
+                    .into_iter()
+                    .find(|region| state.region_controller(*region) != Some(player))
+                    .unwrap_or(Region::ALL[0]);
+                ew_place_influence.send(PlaceInfluence { player, region });
+            }
+
+            let chips_in_area = |marker: usize| {
+                chips
+                    .iter()
+                    .filter(|area| area.player == player && area.marker == marker)
+                    .count()
+            };
+            let (chips_in_production, chips_in_transport) = (chips_in_area(1), chips_in_area(2));
+
+            let mut hand: Vec<(Entity, &Kard, usize)> = cards_in_hand
+                .iter()
+                .filter(|(_, _, hand)| hand.player == player)
+                .filter_map(|(entity, card, _)| {
+                    card_priority(card.data.card_type, chips_in_production, chips_in_transport)
+                        .map(|priority| (entity, &card.data, priority))
+                })
+                .collect();
+            hand.sort_by_key(|(_, _, priority)| *priority);
+
+            let mut occupied: Vec<usize> = cards_on_table
+                .iter()
+                .filter(|card_on_table| card_on_table.player == player)
+                .map(|card_on_table| card_on_table.marker)
+                .collect();
+
+            for (entity, kard, _) in hand {
+                let play = match state.check_card_play(player, kard, &occupied, None) {
+                    Ok(play) => play,
+                    Err(PlayBlocker::NoFreeSlot) => break,
+                    Err(_) => continue,
+                };
+
+                state.draw_bank(
+                    player,
+                    play.price,
+                    TransactionKind::CardPurchase,
+                    Some(kard.card_type),
+                );
+                occupied.push(play.marker);
+                ew_place_card_on_table.send(PlaceCardOnTable {
+                    card_entity: entity,
+                    marker: play.marker,
+                    player,
+                });
+            }
+
+            ew_advance_phase.send(AdvancePhase);
+        }
+        _ => return,
+    }
+
+    ai_timer.0.reset();
+}
@@ -4,12 +4,14 @@ use bevy::{
     utils::HashMap,
 };
 
+use super::cards::ChipType;
+
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<HandleMap<ImageKey>>();
     app.init_resource::<HandleMap<ImageKey>>();
 
-    app.register_type::<HandleMap<ChipModel>>();
-    app.init_resource::<HandleMap<ChipModel>>();
+    app.register_type::<HandleMap<ChipType>>();
+    app.init_resource::<HandleMap<ChipType>>();
 
     app.register_type::<HandleMap<SfxKey>>();
     app.init_resource::<HandleMap<SfxKey>>();
@@ -43,32 +45,20 @@ impl FromWorld for HandleMap<ImageKey> {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
-pub enum ChipModel {
-    Cocaine,
-    Cannabis,
-}
-
-impl AssetKey for ChipModel {
+impl AssetKey for ChipType {
     type Asset = Scene;
 }
 
-impl FromWorld for HandleMap<ChipModel> {
+impl FromWorld for HandleMap<ChipType> {
     fn from_world(world: &mut World) -> Self {
         let asset_server = world.resource::<AssetServer>();
-        [
-            (
-                ChipModel::Cannabis,
-                asset_server
-                    .load("models/chip-cannabis/chip_for_tabletop_gam_0723233549_preview.obj"),
-            ),
-            (
-                ChipModel::Cocaine,
-                asset_server
-                    .load("models/chip-cocaine/chip_for_tabletop_gam_0723233917_refine.obj"),
-            ),
-        ]
-        .into()
+        ChipType::all()
+            .filter_map(|chip_type| {
+                let model = chip_type.product().model.as_deref()?;
+                Some((chip_type, asset_server.load(model)))
+            })
+            .collect::<HashMap<ChipType, Handle<Scene>>>()
+            .into()
     }
 }
 
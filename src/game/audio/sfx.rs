@@ -1,6 +1,12 @@
-use bevy::{audio::PlaybackMode, prelude::*};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
 
-use crate::game::assets::{HandleMap, SfxKey};
+use crate::game::{
+    assets::{HandleMap, SfxKey},
+    settings::Settings,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(play_sfx);
@@ -10,6 +16,7 @@ fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    settings: Res<Settings>,
 ) {
     let sfx_key = match trigger.event() {
         PlaySfx::Key(key) => *key,
@@ -18,6 +25,7 @@ fn play_sfx(
         source: sfx_handles[&sfx_key].clone_weak(),
         settings: PlaybackSettings {
             mode: PlaybackMode::Despawn,
+            volume: Volume::new(settings.sfx_volume),
             ..default()
         },
     });
@@ -3,11 +3,21 @@ use bevy::{
     prelude::*,
 };
 
-use crate::game::assets::{HandleMap, SoundtrackKey};
+use crate::game::{
+    assets::{HandleMap, SoundtrackKey},
+    settings::Settings,
+};
+
+/// Soundtrack volume at full music volume.
+const SOUNDTRACK_VOLUME: f32 = 0.3;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<IsSoundtrack>();
     app.observe(play_soundtrack);
+    app.add_systems(
+        Update,
+        apply_music_volume.run_if(resource_changed::<Settings>),
+    );
 }
 
 fn play_soundtrack(
@@ -15,6 +25,7 @@ fn play_soundtrack(
     mut commands: Commands,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
     soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    settings: Res<Settings>,
 ) {
     for entity in &soundtrack_query {
         commands.entity(entity).despawn_recursive();
@@ -29,7 +40,7 @@ fn play_soundtrack(
             source: soundtrack_handles[&soundtrack_key].clone_weak(),
             settings: PlaybackSettings {
                 mode: PlaybackMode::Loop,
-                volume: Volume::new(0.3),
+                volume: Volume::new(SOUNDTRACK_VOLUME * settings.music_volume),
                 ..default()
             },
         },
@@ -37,6 +48,13 @@ fn play_soundtrack(
     ));
 }
 
+/// Playing sinks don't pick up volume changes on their own.
+fn apply_music_volume(settings: Res<Settings>, sinks: Query<&AudioSink, With<IsSoundtrack>>) {
+    for sink in &sinks {
+        sink.set_volume(settings.global_volume() * SOUNDTRACK_VOLUME * settings.music_volume);
+    }
+}
+
 /// Trigger this event to play or disable the soundtrack.
 /// Playing a new soundtrack will overwrite the previous one.
 /// Soundtracks will loop.
@@ -0,0 +1,363 @@
+//! Table camera. It orbits a focus point on the table and can be turned,
+//! tilted, panned and zoomed with the mouse, keyboard or a gamepad. Focus
+//! actions and player switches glide it to a preset view instead of
+//! teleporting.
+
+use std::f32::consts::{PI, TAU};
+
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use bevy_la_mesa::{HandArea, PlayArea};
+
+use super::{
+    cards::{GameState, SwitchPlayer, PLAY_SLOTS},
+    input::{ActionState, GameAction},
+    products::PRODUCTS,
+    spawn::level::SpawnBoard,
+    tutorial::Tutorial,
+};
+use crate::{screen::Pause, AppSet, GameCamera};
+
+/// Turn and tilt speed for keys and sticks, in radians per second.
+const ORBIT_SPEED: f32 = 1.8;
+/// Turn and tilt per pixel of mouse movement.
+const MOUSE_ORBIT_SPEED: f32 = 0.006;
+/// Pan speed for keys and sticks, in table units per second.
+const PAN_SPEED: f32 = 10.0;
+/// Pan per pixel of mouse movement, per unit of camera distance.
+const MOUSE_PAN_SPEED: f32 = 0.0015;
+/// Zoom per mouse wheel notch, in table units.
+const ZOOM_STEP: f32 = 1.5;
+/// Zoom speed for keys and triggers, in table units per second.
+const ZOOM_SPEED: f32 = 12.0;
+const MIN_PITCH: f32 = 0.2;
+const MAX_PITCH: f32 = 1.45;
+const MIN_DISTANCE: f32 = 5.0;
+const MAX_DISTANCE: f32 = 35.0;
+/// How far from the centre of the table the focus point may wander.
+const PAN_LIMIT: f32 = 15.0;
+const STICK_DEADZONE: f32 = 0.15;
+const TRANSITION_SECONDS: f32 = 0.6;
+
+/// Where the camera sits: it looks at `focus` from `distance` away, turned
+/// `yaw` around the table and tilted `pitch` above it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+    pub focus: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+}
+
+impl Orbit {
+    /// The default view from `player`'s seat.
+    pub fn player_view(player: usize) -> Self {
+        let (focus, yaw) = if player == 1 {
+            (Vec3::ZERO, 0.0)
+        } else {
+            (Vec3::new(-3.0, 0.0, 0.0), PI)
+        };
+        Orbit {
+            focus,
+            yaw,
+            pitch: (12.0f32).atan2(15.0),
+            distance: Vec2::new(12.0, 15.0).length(),
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        let offset = Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        ) * self.distance;
+        Transform::from_translation(self.focus + offset).looking_at(self.focus, Vec3::Y)
+    }
+
+    /// Blend towards `other`, turning the short way round.
+    fn lerp(&self, other: &Orbit, t: f32) -> Self {
+        let turn = (other.yaw - self.yaw + PI).rem_euclid(TAU) - PI;
+        Orbit {
+            focus: self.focus.lerp(other.focus, t),
+            yaw: self.yaw + turn * t,
+            pitch: self.pitch + (other.pitch - self.pitch) * t,
+            distance: self.distance + (other.distance - self.distance) * t,
+        }
+    }
+
+    fn clamp(&mut self) {
+        self.yaw = self.yaw.rem_euclid(TAU);
+        self.pitch = self.pitch.clamp(MIN_PITCH, MAX_PITCH);
+        self.distance = self.distance.clamp(MIN_DISTANCE, MAX_DISTANCE);
+        self.focus.x = self.focus.x.clamp(-PAN_LIMIT, PAN_LIMIT);
+        self.focus.z = self.focus.z.clamp(-PAN_LIMIT, PAN_LIMIT);
+    }
+}
+
+struct Transition {
+    from: Orbit,
+    to: Orbit,
+    timer: Timer,
+}
+
+#[derive(Resource)]
+pub struct CameraRig {
+    pub orbit: Orbit,
+    transition: Option<Transition>,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        CameraRig {
+            orbit: Orbit::player_view(1),
+            transition: None,
+        }
+    }
+}
+
+impl CameraRig {
+    /// Glide from wherever the camera is now to `to`.
+    pub fn glide_to(&mut self, to: Orbit) {
+        self.transition = Some(Transition {
+            from: self.orbit,
+            to,
+            timer: Timer::from_seconds(TRANSITION_SECONDS, TimerMode::Once),
+        });
+    }
+}
+
+/// Preset views the camera can be sent to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraFocus {
+    /// The view from the active player's seat.
+    Overview,
+    Hand,
+    PlayArea,
+    Chips,
+    /// The other player's play area, seen from the active player's side.
+    Opponent,
+}
+
+#[derive(Event)]
+pub struct FocusCamera(pub CameraFocus);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CameraRig>()
+        .add_event::<FocusCamera>()
+        .observe(reset_camera)
+        .add_systems(
+            Update,
+            (
+                control_camera.in_set(AppSet::RecordInput),
+                (focus_camera, update_camera).chain().in_set(AppSet::Update),
+            )
+                .run_if(in_state(Pause::Running)),
+        );
+}
+
+fn reset_camera(_trigger: Trigger<SpawnBoard>, state: Res<GameState>, mut rig: ResMut<CameraRig>) {
+    rig.orbit = Orbit::player_view(state.player);
+    rig.transition = None;
+}
+
+fn control_camera(
+    time: Res<Time>,
+    mut rig: ResMut<CameraRig>,
+    actions: Res<ActionState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut er_mouse_motion: EventReader<MouseMotion>,
+    mut er_mouse_wheel: EventReader<MouseWheel>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut ew_focus_camera: EventWriter<FocusCamera>,
+    tutorial: Option<Res<Tutorial>>,
+) {
+    // The tutorial's callouts point at fixed spots, so the view stays put.
+    if tutorial.is_some() {
+        er_mouse_motion.clear();
+        er_mouse_wheel.clear();
+        return;
+    }
+
+    let dt = time.delta_seconds();
+    // Turn and tilt, pan (right and forward), and zoom in.
+    let mut orbit = Vec2::ZERO;
+    let mut pan = Vec2::ZERO;
+    let mut zoom = 0.0;
+
+    let mouse_delta: Vec2 = er_mouse_motion.read().map(|motion| motion.delta).sum();
+    if mouse.pressed(MouseButton::Right) {
+        orbit += mouse_delta * MOUSE_ORBIT_SPEED;
+    }
+    if mouse.pressed(MouseButton::Middle) {
+        pan += Vec2::new(-mouse_delta.x, mouse_delta.y) * MOUSE_PAN_SPEED * rig.orbit.distance;
+    }
+    for wheel in er_mouse_wheel.read() {
+        zoom += match wheel.unit {
+            MouseScrollUnit::Line => wheel.y * ZOOM_STEP,
+            MouseScrollUnit::Pixel => wheel.y * ZOOM_STEP / 40.0,
+        };
+    }
+
+    let axis = |negative: GameAction, positive: GameAction| {
+        actions.pressed(positive) as i32 as f32 - actions.pressed(negative) as i32 as f32
+    };
+    orbit += Vec2::new(
+        axis(GameAction::OrbitRight, GameAction::OrbitLeft),
+        axis(GameAction::TiltDown, GameAction::TiltUp),
+    ) * ORBIT_SPEED
+        * dt;
+    pan += Vec2::new(
+        axis(GameAction::PanLeft, GameAction::PanRight),
+        axis(GameAction::PanBack, GameAction::PanForward),
+    ) * PAN_SPEED
+        * dt;
+    zoom += axis(GameAction::ZoomOut, GameAction::ZoomIn) * ZOOM_SPEED * dt;
+
+    for (action, focus) in [
+        (GameAction::FocusOverview, CameraFocus::Overview),
+        (GameAction::FocusHand, CameraFocus::Hand),
+        (GameAction::FocusPlayArea, CameraFocus::PlayArea),
+        (GameAction::FocusChips, CameraFocus::Chips),
+        (GameAction::FocusOpponent, CameraFocus::Opponent),
+    ] {
+        if actions.just_pressed(action) {
+            ew_focus_camera.send(FocusCamera(focus));
+        }
+    }
+
+    for gamepad in gamepads.iter() {
+        let stick = |x: GamepadAxisType, y: GamepadAxisType| {
+            let value = Vec2::new(
+                gamepad_axes
+                    .get(GamepadAxis::new(gamepad, x))
+                    .unwrap_or(0.0),
+                gamepad_axes
+                    .get(GamepadAxis::new(gamepad, y))
+                    .unwrap_or(0.0),
+            );
+            if value.length() < STICK_DEADZONE {
+                Vec2::ZERO
+            } else {
+                value
+            }
+        };
+        orbit += stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY)
+            * Vec2::new(1.0, -1.0)
+            * ORBIT_SPEED
+            * dt;
+        pan += stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY) * PAN_SPEED * dt;
+    }
+
+    if orbit == Vec2::ZERO && pan == Vec2::ZERO && zoom == 0.0 {
+        return;
+    }
+
+    // Taking the controls cancels any glide in progress.
+    rig.transition = None;
+    let view = &mut rig.orbit;
+    view.yaw -= orbit.x;
+    view.pitch += orbit.y;
+    view.distance -= zoom;
+    let right = Vec3::new(view.yaw.cos(), 0.0, -view.yaw.sin());
+    let forward = Vec3::new(-view.yaw.sin(), 0.0, -view.yaw.cos());
+    view.focus += right * pan.x + forward * pan.y;
+    view.clamp();
+}
+
+fn focus_camera(
+    mut er_focus_camera: EventReader<FocusCamera>,
+    mut er_switch_player: EventReader<SwitchPlayer>,
+    mut rig: ResMut<CameraRig>,
+    state: Res<GameState>,
+    hand_areas: Query<(&HandArea, &GlobalTransform)>,
+    play_areas: Query<(&PlayArea, &GlobalTransform)>,
+) {
+    let mut target = None;
+    for event in er_switch_player.read() {
+        target = Some(Orbit::player_view(event.player));
+    }
+
+    for FocusCamera(focus) in er_focus_camera.read() {
+        let player = state.player;
+        let opponent = if player == 1 { 2 } else { 1 };
+        let seat = Orbit::player_view(player);
+        let slots_of = |player: usize| {
+            let slots: Vec<Vec3> = play_areas
+                .iter()
+                .filter(|(area, _)| area.player == player && area.marker <= PLAY_SLOTS)
+                .map(|(_, transform)| transform.translation())
+                .collect();
+            slots.iter().sum::<Vec3>() / slots.len().max(1) as f32
+        };
+
+        target = Some(match focus {
+            CameraFocus::Overview => seat,
+            CameraFocus::Hand => Orbit {
+                focus: hand_areas
+                    .iter()
+                    .find(|(area, _)| area.player == player)
+                    .map_or(seat.focus, |(_, transform)| transform.translation()),
+                pitch: 0.9,
+                distance: 9.0,
+                ..seat
+            },
+            CameraFocus::PlayArea => Orbit {
+                focus: slots_of(player),
+                pitch: 1.0,
+                distance: 13.0,
+                ..seat
+            },
+            CameraFocus::Chips => {
+                let side = if player == 1 { 1.0 } else { -1.0 };
+                let stacks: Vec3 = PRODUCTS
+                    .iter()
+                    .map(|product| {
+                        let (x, z) = product.stack_position;
+                        Vec3::new(x + 2.0, 0.0, z * side)
+                    })
+                    .sum();
+                Orbit {
+                    focus: stacks / PRODUCTS.len() as f32,
+                    pitch: 1.1,
+                    distance: 8.0,
+                    ..seat
+                }
+            }
+            CameraFocus::Opponent => Orbit {
+                focus: slots_of(opponent),
+                pitch: 0.9,
+                distance: 14.0,
+                ..seat
+            },
+        });
+    }
+
+    if let Some(target) = target {
+        rig.glide_to(target);
+    }
+}
+
+fn update_camera(
+    time: Res<Time>,
+    mut rig: ResMut<CameraRig>,
+    mut camera: Query<&mut Transform, With<GameCamera>>,
+) {
+    let rig = rig.as_mut();
+    if let Some(transition) = rig.transition.as_mut() {
+        transition.timer.tick(time.delta());
+        let t = transition.timer.fraction();
+        // Ease in and out.
+        let eased = t * t * (3.0 - 2.0 * t);
+        rig.orbit = transition.from.lerp(&transition.to, eased);
+        if transition.timer.finished() {
+            rig.transition = None;
+        }
+    }
+
+    for mut transform in &mut camera {
+        *transform = rig.orbit.transform();
+    }
+}
@@ -0,0 +1,189 @@
+//! Single-player campaign. Scenarios are read from
+//! `assets/campaign/scenarios.ron` and change how a match is set up: starting
+//! bank, deck, computer opponents, rules and the bank needed to win.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ai::AiPlayers,
+    cards::{
+        load_starting_deck, CardType, ChipType, GameOver, GameState, Kard, TURN_LIMIT, WINNING_BANK,
+    },
+    effects::Modifier,
+    objectives::{Objective, Objectives},
+    products::PRODUCTS,
+    settings::{read_storage, write_storage, Storage},
+    spawn::level::SpawnBoard,
+    territory::load_influence_cards,
+};
+
+/// Where campaign progress is kept between sessions.
+#[cfg(not(target_family = "wasm"))]
+const PROGRESS_STORAGE: Storage = Storage::Data("campaign-progress.ron");
+#[cfg(target_family = "wasm")]
+const PROGRESS_STORAGE: Storage = Storage::Data("bevy_cartel_campaign_progress");
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum ScenarioCard {
+    Attack,
+    Export,
+    LocalMarket,
+    Train,
+    Truck,
+    Warehouse,
+    Bribe,
+    Influence,
+    /// A production card, by product name.
+    Production(String),
+}
+
+impl ScenarioCard {
+    pub fn card_type(&self) -> Option<CardType> {
+        Some(match self {
+            ScenarioCard::Attack => CardType::Attack,
+            ScenarioCard::Export => CardType::Export,
+            ScenarioCard::LocalMarket => CardType::LocalMarket,
+            ScenarioCard::Train => CardType::Train,
+            ScenarioCard::Truck => CardType::Truck,
+            ScenarioCard::Warehouse => CardType::Warehouse,
+            ScenarioCard::Bribe => CardType::Bribe,
+            ScenarioCard::Influence => CardType::Influence,
+            ScenarioCard::Production(name) => CardType::Production(ChipType(
+                PRODUCTS
+                    .iter()
+                    .position(|product| product.name == name.as_str())?,
+            )),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    pub starting_bank: i32,
+    pub target_bank: i32,
+    pub turn_limit: usize,
+    /// Cards in every player's deck, with how many of each.
+    pub deck: Vec<(ScenarioCard, usize)>,
+    pub ai_players: Vec<usize>,
+    /// Modifiers on every player for the whole match.
+    pub rules: Vec<Modifier>,
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+}
+
+impl Scenario {
+    pub fn load_deck(&self) -> Vec<Kard> {
+        let templates: Vec<Kard> = load_starting_deck()
+            .into_iter()
+            .chain(load_influence_cards(1))
+            .collect();
+
+        let mut deck = vec![];
+        for (card, count) in &self.deck {
+            let template = card.card_type().and_then(|card_type| {
+                templates
+                    .iter()
+                    .find(|template| template.card_type == card_type)
+            });
+            match template {
+                Some(template) => deck.extend(std::iter::repeat(template.clone()).take(*count)),
+                None => warn!("Scenario {} has an unknown card {:?}", self.name, card),
+            }
+        }
+        deck
+    }
+}
+
+#[derive(Resource)]
+pub struct Campaign {
+    pub scenarios: Vec<Scenario>,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        let scenarios = ron::from_str(include_str!("../../assets/campaign/scenarios.ron"))
+            .expect("campaign scenarios should be valid RON");
+        Self { scenarios }
+    }
+}
+
+/// Scenario being played, as an index into [`Campaign::scenarios`].
+#[derive(Resource)]
+pub struct ActiveScenario(pub usize);
+
+/// Campaign progress, saved after every scenario won.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    /// Scenarios won so far; the next one is unlocked.
+    pub completed: usize,
+}
+
+impl CampaignProgress {
+    fn load() -> Self {
+        read_storage(PROGRESS_STORAGE)
+            .and_then(|contents| ron::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        match ron::to_string(self) {
+            Ok(contents) => {
+                if let Err(error) = write_storage(PROGRESS_STORAGE, &contents) {
+                    warn!("Couldn't save campaign progress: {}", error);
+                }
+            }
+            Err(error) => warn!("Couldn't save campaign progress: {}", error),
+        }
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Campaign>()
+        .insert_resource(CampaignProgress::load())
+        .observe(apply_scenario)
+        .add_systems(Update, record_campaign_result);
+}
+
+/// Sets the match up for the active scenario, or back to a standard match.
+fn apply_scenario(
+    _trigger: Trigger<SpawnBoard>,
+    campaign: Res<Campaign>,
+    active_scenario: Option<Res<ActiveScenario>>,
+    mut state: ResMut<GameState>,
+    mut ai_players: ResMut<AiPlayers>,
+    mut objectives: ResMut<Objectives>,
+) {
+    let Some(scenario) = active_scenario.and_then(|active| campaign.scenarios.get(active.0)) else {
+        state.rules.clear();
+        state.target_bank = WINNING_BANK;
+        state.turn_limit = TURN_LIMIT;
+        *objectives = Objectives::default();
+        return;
+    };
+
+    state.bank = vec![scenario.starting_bank; state.num_players()];
+    state.rules.clone_from(&scenario.rules);
+    state.target_bank = scenario.target_bank;
+    state.turn_limit = scenario.turn_limit;
+    ai_players.0.clone_from(&scenario.ai_players);
+    *objectives = Objectives::new(&scenario.objectives);
+}
+
+fn record_campaign_result(
+    mut er_game_over: EventReader<GameOver>,
+    active_scenario: Option<Res<ActiveScenario>>,
+    mut progress: ResMut<CampaignProgress>,
+) {
+    for event in er_game_over.read() {
+        let Some(active_scenario) = &active_scenario else {
+            continue;
+        };
+        if event.player_won == 1 && progress.completed <= active_scenario.0 {
+            progress.completed = active_scenario.0 + 1;
+            progress.save();
+        }
+    }
+}
@@ -1,29 +1,61 @@
-use bevy::{app::App, prelude::*};
+use bevy::{app::App, prelude::*, utils::HashSet};
 use bevy_la_mesa::events::{
     AlignCardsInHand, AlignChipsOnTable, PlaceCardOffTable, PlaceCardOnTable,
 };
 use bevy_la_mesa::{Card, CardMetadata, CardOnTable, Chip, ChipArea, Deck};
 
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::fmt::Debug;
 use std::marker::Send;
 
-use crate::GameCamera;
+use super::effects::{
+    Effect, EffectDuration, EffectType, Modifier, Stacking, Stat, Trigger, TriggerAction,
+};
+use super::loans::CREDIT_LIMIT;
+use super::products::{Product, PRODUCTS};
+use super::research::Technology;
+use super::territory::Region;
+use super::transport::{interception_chance, SeizeChip};
+use super::warehouse::{PRODUCTION_CAPACITY, TRANSPORT_CAPACITY, WAREHOUSE_UPGRADE};
+use crate::screen::Pause;
+
+/// Shortest time between two phase changes, at normal animation speed.
+pub const PHASE_DELAY_SECONDS: f32 = 0.3;
 
 #[derive(Resource)]
 pub struct PhaseTimer(pub Timer);
 
-#[derive(Clone, Copy, Debug, Default)]
+/// Seeded random source for everything left to chance in a match, so a game
+/// can be replayed from its seed.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum CardType {
     #[default]
     Attack,
     BigDeal,
-    Cocaine,
-    Cannabis,
+    Production(ChipType),
     Drought,
     Export,
     LocalMarket,
     Train,
     Truck,
+    Warehouse,
+    Bribe,
+    Influence,
 }
 
 #[derive(Component)]
@@ -31,11 +63,105 @@ pub struct ActiveEventCard {
     pub player: usize,
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
-pub enum ChipType {
-    #[default]
-    Cocaine,
-    Cannabis,
+impl CardType {
+    pub fn name(&self) -> String {
+        match self {
+            CardType::Production(chip_type) => chip_type.product().name.to_string(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    /// What the card does, in general terms.
+    pub fn rules(&self) -> &'static str {
+        match self {
+            CardType::Production(_) => {
+                "Drops chips of its product into your production area. Resolves left to right, so the leftmost cards fill storage first."
+            }
+            CardType::Truck | CardType::Train => {
+                "Moves chips that have rested a turn from production to transport, up to the weight it can carry, starting with the product of the card in the slot to its left. Shipments may be intercepted."
+            }
+            CardType::Export | CardType::LocalMarket => {
+                "Sells chips that have rested a turn in transport, up to the weight it can move."
+            }
+            CardType::Warehouse => "Adds room to your production and transport areas for good.",
+            CardType::Bribe => {
+                "Halves the chance of your shipments being intercepted for two turns. A raid ends it."
+            }
+            CardType::Attack => {
+                "Raids your opponent: less production and transport, more interceptions and a fine every turn."
+            }
+            CardType::Influence => "Gives you a marker to place on the territory map.",
+            CardType::Drought => "Event: less production and dearer cards for three turns.",
+            CardType::BigDeal => "Event: your first sale this round pays 50% more.",
+        }
+    }
+
+    /// Weight a transport or sales card moves, before modifiers.
+    pub fn capacity(&self) -> i32 {
+        match self {
+            CardType::Truck | CardType::LocalMarket => 20,
+            CardType::Train | CardType::Export => 50,
+            _ => 0,
+        }
+    }
+}
+
+/// Chips a production card drops, before modifiers.
+pub const PRODUCTION_PER_CARD: i32 = 5;
+/// Cards a player can have in play at once.
+pub const PLAY_SLOTS: usize = 5;
+
+/// Why a card in hand can't be played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlayBlocker {
+    NotYourTurn,
+    WrongPhase,
+    Bankrupt,
+    NoFreeSlot,
+    SlotTaken,
+    /// The card costs this much, more than the bank holds.
+    CannotAfford(i32),
+}
+
+impl PlayBlocker {
+    pub fn message(&self) -> String {
+        match self {
+            PlayBlocker::NotYourTurn => "It's not your turn".to_string(),
+            PlayBlocker::WrongPhase => "Cards can only be played in the play phase".to_string(),
+            PlayBlocker::Bankrupt => "Bankrupt players can't play cards".to_string(),
+            PlayBlocker::NoFreeSlot => format!("All {} play slots are taken", PLAY_SLOTS),
+            PlayBlocker::SlotTaken => "That slot is taken".to_string(),
+            PlayBlocker::CannotAfford(price) => format!("Costs ${}, more than you have", price),
+        }
+    }
+}
+
+/// A legal play: what it costs and the slot it goes into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CardPlay {
+    pub price: i32,
+    pub marker: usize,
+}
+
+/// A product, as an index into [`PRODUCTS`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub struct ChipType(pub usize);
+
+impl ChipType {
+    pub const COCAINE: ChipType = ChipType(0);
+    pub const CANNABIS: ChipType = ChipType(1);
+
+    pub fn all() -> impl Iterator<Item = ChipType> {
+        (0..PRODUCTS.len()).map(ChipType)
+    }
+
+    pub fn product(&self) -> &'static Product {
+        &PRODUCTS[self.0]
+    }
+
+    pub fn price(&self) -> i32 {
+        self.product().price
+    }
 }
 
 #[derive(Default, Clone, Debug)]
@@ -43,6 +169,7 @@ pub struct Kard {
     pub card_type: CardType,
     pub price: i32,
     pub filename: String,
+    pub flavour: String,
 }
 
 impl CardMetadata for Kard {
@@ -54,68 +181,91 @@ impl CardMetadata for Kard {
 }
 
 pub fn load_playing_deck(num_players: usize) -> Vec<Kard> {
+    (0..num_players)
+        .flat_map(|_| load_starting_deck())
+        .collect()
+}
+
+/// The cards each player starts the game with.
+pub fn load_starting_deck() -> Vec<Kard> {
     let attack = Kard {
         card_type: CardType::Attack,
         price: 300,
         filename: "tarjetas/attack.png".to_string(),
-    };
-
-    let cocaine = Kard {
-        card_type: CardType::Cocaine,
-        price: 1000,
-        filename: "tarjetas/cocaine.png".to_string(),
+        flavour: "Nothing personal. It's just business.".to_string(),
     };
 
     let export = Kard {
         card_type: CardType::Export,
         price: 0,
         filename: "tarjetas/export.png".to_string(),
+        flavour: "The real money is across the border.".to_string(),
     };
 
     let local_market = Kard {
         card_type: CardType::LocalMarket,
         price: 0,
         filename: "tarjetas/local-market.png".to_string(),
-    };
-
-    let marijuana = Kard {
-        card_type: CardType::Cannabis,
-        price: 500,
-        filename: "tarjetas/marijuana.png".to_string(),
+        flavour: "Small bags, steady customers.".to_string(),
     };
 
     let train = Kard {
         card_type: CardType::Train,
         price: 600,
         filename: "tarjetas/train.png".to_string(),
+        flavour: "Slow, heavy and hard to stop.".to_string(),
     };
 
     let truck = Kard {
         card_type: CardType::Truck,
         price: 300,
         filename: "tarjetas/truck.png".to_string(),
+        flavour: "Nobody looks twice at a produce truck.".to_string(),
+    };
+
+    // No art yet; uses the debug face until a card is drawn.
+    let warehouse = Kard {
+        card_type: CardType::Warehouse,
+        price: 800,
+        filename: "tarjetas/debug.png".to_string(),
+        flavour: "Room to grow, if nobody comes knocking.".to_string(),
+    };
+
+    let bribe = Kard {
+        card_type: CardType::Bribe,
+        price: 400,
+        filename: "tarjetas/archive-1.png".to_string(),
+        flavour: "Everybody has a price.".to_string(),
     };
 
     let mut deck: Vec<Kard> = vec![];
-    for _ in 0..num_players {
-        deck.push(cocaine.clone());
-        deck.push(cocaine.clone());
+    for chip_type in ChipType::all() {
+        let product = chip_type.product();
+        for _ in 0..product.cards_per_player {
+            deck.push(Kard {
+                card_type: CardType::Production(chip_type),
+                price: product.card_price,
+                filename: product.card_filename.to_string(),
+                flavour: format!("Fresh {} from the farm.", product.name.to_lowercase()),
+            });
+        }
+    }
 
-        deck.push(marijuana.clone());
-        deck.push(marijuana.clone());
+    deck.push(truck.clone());
+    deck.push(truck.clone());
 
-        deck.push(truck.clone());
-        deck.push(truck.clone());
+    deck.push(train.clone());
 
-        deck.push(train.clone());
+    deck.push(local_market.clone());
+    deck.push(local_market.clone());
 
-        deck.push(local_market.clone());
-        deck.push(local_market.clone());
+    deck.push(export.clone());
 
-        deck.push(export.clone());
+    deck.push(attack.clone());
 
-        deck.push(attack.clone());
-    }
+    deck.push(warehouse.clone());
+
+    deck.push(bribe.clone());
 
     deck
 }
@@ -125,12 +275,14 @@ pub fn load_event_deck(num_players: usize) -> Vec<Kard> {
         card_type: CardType::BigDeal,
         price: 0,
         filename: "tarjetas/big-deal.png".to_string(),
+        flavour: "A buyer with deep pockets is in town.".to_string(),
     };
 
     let drought = Kard {
         card_type: CardType::Drought,
         price: 0,
         filename: "tarjetas/drought.png".to_string(),
+        flavour: "The fields are dry this season.".to_string(),
     };
 
     let mut deck: Vec<Kard> = vec![];
@@ -142,7 +294,7 @@ pub fn load_event_deck(num_players: usize) -> Vec<Kard> {
     deck
 }
 
-#[derive(Default, Debug, PartialEq)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
 pub enum TurnPhase {
     #[default]
     Prepare,
@@ -156,20 +308,77 @@ pub enum TurnPhase {
     End,
 }
 
+impl TurnPhase {
+    /// Every phase, in turn order.
+    pub const ALL: [TurnPhase; 9] = [
+        TurnPhase::Prepare,
+        TurnPhase::PlaceCardsOnTable,
+        TurnPhase::DrawEventCard,
+        TurnPhase::ApplyEventCard,
+        TurnPhase::ApplyProductionCards,
+        TurnPhase::ApplyTransportationCards,
+        TurnPhase::ApplySalesCards,
+        TurnPhase::ApplyActionCards,
+        TurnPhase::End,
+    ];
+
+    /// Short name for the phase tracker.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TurnPhase::Prepare => "Prepare",
+            TurnPhase::PlaceCardsOnTable => "Play",
+            TurnPhase::DrawEventCard => "Event",
+            TurnPhase::ApplyEventCard => "Resolve",
+            TurnPhase::ApplyProductionCards => "Produce",
+            TurnPhase::ApplyTransportationCards => "Transport",
+            TurnPhase::ApplySalesCards => "Sell",
+            TurnPhase::ApplyActionCards => "Actions",
+            TurnPhase::End => "End",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Loan {
+    pub player: usize,
+    pub principal: i32,
+    pub outstanding: i32,
+    /// Interest charged at the end of each of the borrower's turns, in percent.
+    pub interest_rate: i32,
+    pub turn_number: usize,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub enum EffectType {
-    Drought,
-    Attack,
+pub enum TransactionKind {
+    CardPurchase,
+    ChipSale,
+    EffectPenalty,
+    Research,
+    Trade,
+    LoanTaken,
+    LoanRepayment,
+    Interest,
+    Liquidation,
 }
 
+/// A single movement of money in or out of a player's bank.
 #[derive(Clone, Debug)]
-pub struct Effect {
-    pub effect_type: EffectType,
+pub struct Transaction {
     pub player: usize,
+    pub amount: i32,
+    pub kind: TransactionKind,
     pub turn_number: usize,
-    pub duration: usize,
+    pub phase: TurnPhase,
+    pub card: Option<CardType>,
 }
 
+/// Money each player starts a standard match with.
+pub const STARTING_BANK: i32 = 5000;
+/// Bank a player needs to win a standard match outright.
+pub const WINNING_BANK: i32 = 50000;
+/// Turn after which the richest player wins a standard match.
+pub const TURN_LIMIT: usize = 20;
+
 #[derive(Resource)]
 pub struct GameState {
     pub turn_number: usize,
@@ -177,6 +386,23 @@ pub struct GameState {
     pub phase: TurnPhase,
     pub player: usize,
     pub bank: Vec<i32>,
+    pub loans: Vec<Loan>,
+    pub bankrupt: Vec<bool>,
+    pub ledger: Vec<Transaction>,
+    pub warehouses: Vec<usize>,
+    pub research: Vec<Vec<Technology>>,
+    /// Markers on the territory map, indexed by region then player.
+    pub influence: Vec<Vec<usize>>,
+    /// Markers from played Influence cards not yet put on a region.
+    pub pending_influence: Vec<usize>,
+    /// Modifiers every player is under for the whole match, e.g. a
+    /// scenario's permanent drought.
+    pub rules: Vec<Modifier>,
+    pub target_bank: i32,
+    pub turn_limit: usize,
+    pub chips_produced: Vec<usize>,
+    /// Set once the match is decided.
+    pub winner: Option<usize>,
     num_players: usize,
 }
 
@@ -192,11 +418,17 @@ impl GameState {
             TurnPhase::ApplySalesCards => TurnPhase::ApplyActionCards,
             TurnPhase::ApplyActionCards => TurnPhase::End,
             TurnPhase::End => {
-                if self.player == self.num_players {
-                    self.turn_number += 1;
-                    self.player = 1;
-                } else {
-                    self.player += 1;
+                // Bankrupt players sit their turns out.
+                for _ in 0..self.num_players {
+                    if self.player == self.num_players {
+                        self.turn_number += 1;
+                        self.player = 1;
+                    } else {
+                        self.player += 1;
+                    }
+                    if !self.is_bankrupt(self.player) {
+                        break;
+                    }
                 }
                 TurnPhase::Prepare
             }
@@ -209,40 +441,125 @@ impl GameState {
             turn_number: 1,
             phase: TurnPhase::Prepare,
             player: 1,
-            bank: vec![5000; num_players],
+            bank: vec![STARTING_BANK; num_players],
+            loans: vec![],
+            bankrupt: vec![false; num_players],
+            ledger: vec![],
+            warehouses: vec![0; num_players],
+            research: vec![vec![]; num_players],
+            influence: vec![vec![0; num_players]; Region::ALL.len()],
+            pending_influence: vec![0; num_players],
+            rules: vec![],
+            target_bank: WINNING_BANK,
+            turn_limit: TURN_LIMIT,
+            chips_produced: vec![0; num_players],
+            winner: None,
             num_players,
             effects: vec![],
         }
     }
 
+    pub fn num_players(&self) -> usize {
+        self.num_players
+    }
+
+    /// Marker of the deck `player` draws from.
+    pub fn deck_marker(&self, player: usize) -> usize {
+        2 * player + 1
+    }
+
+    /// Marker of the pile `player`'s played cards go to.
+    pub fn discard_marker(&self, player: usize) -> usize {
+        2 * player + 2
+    }
+
+    /// Back to the start of a standard match with the same players.
     pub fn reset(&mut self) {
         self.turn_number = 1;
         self.phase = TurnPhase::Prepare;
         self.player = 1;
-        self.bank = vec![0; self.num_players];
+        self.effects = vec![];
+        self.bank = vec![STARTING_BANK; self.num_players];
+        self.loans = vec![];
+        self.bankrupt = vec![false; self.num_players];
+        self.ledger = vec![];
+        self.warehouses = vec![0; self.num_players];
+        self.research = vec![vec![]; self.num_players];
+        self.influence = vec![vec![0; self.num_players]; Region::ALL.len()];
+        self.pending_influence = vec![0; self.num_players];
+        self.rules = vec![];
+        self.target_bank = WINNING_BANK;
+        self.turn_limit = TURN_LIMIT;
+        self.chips_produced = vec![0; self.num_players];
+        self.winner = None;
     }
 
-    pub fn change_balance(&mut self, player: usize, amount: i32) {
+    /// Decides the match for `player_won`. Gives the [`GameOver`] to send,
+    /// or `None` if the match was already over.
+    pub fn finish(&mut self, player_won: usize) -> Option<GameOver> {
+        if self.winner.is_some() {
+            return None;
+        }
+        self.winner = Some(player_won);
+        Some(GameOver { player_won })
+    }
+
+    pub fn change_balance(
+        &mut self,
+        player: usize,
+        amount: i32,
+        kind: TransactionKind,
+        card: Option<CardType>,
+    ) {
         self.bank[player - 1] += amount;
+        self.ledger.push(Transaction {
+            player,
+            amount,
+            kind,
+            turn_number: self.turn_number,
+            phase: self.phase,
+            card,
+        });
     }
 
-    pub fn draw_bank(&mut self, player: usize, amount: i32) {
-        self.bank[player - 1] -= amount;
+    pub fn draw_bank(
+        &mut self,
+        player: usize,
+        amount: i32,
+        kind: TransactionKind,
+        card: Option<CardType>,
+    ) {
+        self.change_balance(player, -amount, kind, card);
     }
 
     pub fn get_balance(&self, player: usize) -> i32 {
         self.bank[player - 1]
     }
 
-    pub fn add_effect(&mut self, effect_type: EffectType, duration: usize, player: usize) {
-        self.effects
-            .retain(|effect| !(effect.effect_type == effect_type && effect.player == player));
+    pub fn add_effect(&mut self, effect_type: EffectType, player: usize, source: Option<CardType>) {
+        let definition = effect_type.definition();
+        let running =
+            |effect: &Effect| effect.effect_type == effect_type && effect.player == player;
+
+        match definition.stacking {
+            Stacking::Replace => self.effects.retain(|effect| !running(effect)),
+            Stacking::Stack => {}
+            Stacking::KeepExisting => {
+                if self.effects.iter().any(running) {
+                    return;
+                }
+            }
+        }
 
         self.effects.push(Effect {
-            effect_type: effect_type,
-            player: player,
+            effect_type,
+            player,
             turn_number: self.turn_number,
-            duration,
+            player_turns_left: match definition.duration {
+                EffectDuration::PlayerTurns(turns) => turns,
+                EffectDuration::Rounds(_) => 0,
+            },
+            source,
         });
     }
 
@@ -255,9 +572,316 @@ impl GameState {
     }
 
     pub fn remove_expired_effects(&mut self) {
+        let turn_number = self.turn_number;
         self.effects
-            .retain(|effect| effect.turn_number + effect.duration > self.turn_number);
+            .retain(|effect| !effect.is_expired(turn_number));
+    }
+
+    /// Counts down effects measured in the turns of `player`, who just ended one.
+    pub fn tick_player_turn_effects(&mut self, player: usize) {
+        for effect in self
+            .effects
+            .iter_mut()
+            .filter(|effect| effect.player == player)
+        {
+            effect.player_turns_left = effect.player_turns_left.saturating_sub(1);
+        }
+    }
+
+    pub fn has_researched(&self, player: usize, technology: Technology) -> bool {
+        self.research[player - 1].contains(&technology)
+    }
+
+    pub fn can_research(&self, player: usize, technology: Technology) -> bool {
+        let definition = technology.definition();
+        !self.has_researched(player, technology)
+            && definition
+                .requires
+                .map_or(true, |required| self.has_researched(player, required))
+            && definition.cost <= self.get_balance(player)
+    }
+
+    /// The player with strictly the most markers in `region`, if any.
+    pub fn region_controller(&self, region: Region) -> Option<usize> {
+        let markers = &self.influence[region.index()];
+        let most = *markers.iter().max()?;
+        let mut leaders = markers.iter().enumerate().filter(|(_, m)| **m == most);
+        match (leaders.next(), leaders.next()) {
+            (Some((index, _)), None) if most > 0 => Some(index + 1),
+            _ => None,
+        }
+    }
+
+    /// Applies every effect, technology and controlled region of `player`, and
+    /// the match rules, to a base value of `stat`.
+    pub fn modify_stat(&self, player: usize, stat: Stat, base: i32) -> i32 {
+        let effect_modifiers = self
+            .effects
+            .iter()
+            .filter(|effect| effect.player == player)
+            .flat_map(|effect| effect.effect_type.definition().modifiers);
+        let research_modifiers = self.research[player - 1]
+            .iter()
+            .flat_map(|technology| technology.definition().modifiers);
+        let region_modifiers = Region::ALL
+            .iter()
+            .filter(|region| self.region_controller(**region) == Some(player))
+            .flat_map(|region| region.definition().modifiers);
+
+        let mut flat = 0;
+        let mut percent = 0;
+        for modifier in effect_modifiers
+            .chain(research_modifiers)
+            .chain(region_modifiers)
+            .chain(&self.rules)
+        {
+            match *modifier {
+                Modifier::Add(s, amount) if s == stat => flat += amount,
+                Modifier::Percent(s, amount) if s == stat => percent += amount,
+                _ => {}
+            }
+        }
+        ((base + flat) * (100 + percent) / 100).max(0)
     }
+
+    /// Runs the hooks of every effect on `player` that listen to `trigger`.
+    pub fn fire_trigger(&mut self, player: usize, trigger: Trigger) {
+        let mut charges = vec![];
+        let mut expired = vec![];
+        for (index, effect) in self.effects.iter().enumerate() {
+            if effect.player != player {
+                continue;
+            }
+            for (hook_trigger, action) in effect.effect_type.definition().hooks {
+                if *hook_trigger != trigger {
+                    continue;
+                }
+                match action {
+                    TriggerAction::Charge(amount) => charges.push((*amount, effect.source)),
+                    TriggerAction::Expire => expired.push(index),
+                }
+            }
+        }
+
+        for (amount, source) in charges {
+            self.draw_bank(player, amount, TransactionKind::EffectPenalty, source);
+        }
+        for index in expired.into_iter().rev() {
+            self.effects.remove(index);
+        }
+    }
+
+    pub fn get_loans(&self, player: usize) -> Vec<Loan> {
+        self.loans
+            .iter()
+            .filter(|loan| loan.player == player)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_debt(&self, player: usize) -> i32 {
+        self.loans
+            .iter()
+            .filter(|loan| loan.player == player)
+            .map(|loan| loan.outstanding)
+            .sum()
+    }
+
+    pub fn get_available_credit(&self, player: usize) -> i32 {
+        (CREDIT_LIMIT - self.get_debt(player)).max(0)
+    }
+
+    /// Lends `amount` to `player` if it fits under the credit limit.
+    pub fn take_loan(&mut self, player: usize, amount: i32, interest_rate: i32) -> bool {
+        if amount <= 0 || amount > self.get_available_credit(player) {
+            return false;
+        }
+
+        self.loans.push(Loan {
+            player,
+            principal: amount,
+            outstanding: amount,
+            interest_rate,
+            turn_number: self.turn_number,
+        });
+        self.change_balance(player, amount, TransactionKind::LoanTaken, None);
+        true
+    }
+
+    /// Pays back up to `amount` of `player`'s debt, oldest loans first.
+    pub fn repay_loan(&mut self, player: usize, amount: i32) -> i32 {
+        let mut remaining = amount.min(self.get_balance(player)).max(0);
+        let mut repaid = 0;
+
+        for loan in self.loans.iter_mut().filter(|loan| loan.player == player) {
+            if remaining <= 0 {
+                break;
+            }
+            let payment = remaining.min(loan.outstanding);
+            loan.outstanding -= payment;
+            remaining -= payment;
+            repaid += payment;
+        }
+
+        self.loans.retain(|loan| loan.outstanding > 0);
+        if repaid > 0 {
+            self.draw_bank(player, repaid, TransactionKind::LoanRepayment, None);
+        }
+        repaid
+    }
+
+    /// Charges one turn of interest on every loan held by `player`.
+    pub fn charge_interest(&mut self, player: usize) -> i32 {
+        let interest: i32 = self
+            .loans
+            .iter()
+            .filter(|loan| loan.player == player)
+            .map(|loan| (loan.outstanding * loan.interest_rate + 99) / 100)
+            .sum();
+
+        if interest > 0 {
+            self.draw_bank(player, interest, TransactionKind::Interest, None);
+        }
+        interest
+    }
+
+    pub fn declare_bankrupt(&mut self, player: usize) {
+        self.bankrupt[player - 1] = true;
+    }
+
+    pub fn is_bankrupt(&self, player: usize) -> bool {
+        self.bankrupt[player - 1]
+    }
+
+    /// Whether `player` may rearrange the table right now.
+    pub fn check_table_change(&self, player: usize) -> Result<(), PlayBlocker> {
+        if player != self.player {
+            return Err(PlayBlocker::NotYourTurn);
+        }
+        if self.phase != TurnPhase::PlaceCardsOnTable {
+            return Err(PlayBlocker::WrongPhase);
+        }
+        if self.is_bankrupt(player) {
+            return Err(PlayBlocker::Bankrupt);
+        }
+        Ok(())
+    }
+
+    /// Whether `player` may play `kard` from their hand now, into `slot` or
+    /// the leftmost free slot. `occupied` are the slots they already use.
+    /// Shared by the rules and the UI.
+    pub fn check_card_play(
+        &self,
+        player: usize,
+        kard: &Kard,
+        occupied: &[usize],
+        slot: Option<usize>,
+    ) -> Result<CardPlay, PlayBlocker> {
+        self.check_table_change(player)?;
+        let marker = match slot {
+            Some(slot) if occupied.contains(&slot) => return Err(PlayBlocker::SlotTaken),
+            Some(slot) => slot,
+            None => (1..=PLAY_SLOTS)
+                .find(|marker| !occupied.contains(marker))
+                .ok_or(PlayBlocker::NoFreeSlot)?,
+        };
+        let price = self.modify_stat(player, Stat::CardCost, kard.price);
+        if price > self.get_balance(player) {
+            return Err(PlayBlocker::CannotAfford(price));
+        }
+        Ok(CardPlay { price, marker })
+    }
+
+    /// How many chips `player` can keep in the given chip area.
+    pub fn get_storage_capacity(&self, player: usize, area: usize) -> usize {
+        let base = match area {
+            1 => PRODUCTION_CAPACITY,
+            _ => TRANSPORT_CAPACITY,
+        };
+        base + self.warehouses[player - 1] * WAREHOUSE_UPGRADE
+    }
+
+    pub fn get_transactions(&self, player: usize) -> Vec<Transaction> {
+        self.ledger
+            .iter()
+            .filter(|transaction| transaction.player == player)
+            .cloned()
+            .collect()
+    }
+
+    /// Sums a player's transactions by kind, in order of first appearance.
+    pub fn get_ledger_breakdown(&self, player: usize) -> Vec<(TransactionKind, i32)> {
+        let mut breakdown: Vec<(TransactionKind, i32)> = vec![];
+        for transaction in self.ledger.iter().filter(|t| t.player == player) {
+            match breakdown
+                .iter_mut()
+                .find(|(kind, _)| *kind == transaction.kind)
+            {
+                Some((_, total)) => *total += transaction.amount,
+                None => breakdown.push((transaction.kind, transaction.amount)),
+            }
+        }
+        breakdown
+    }
+
+    /// Bank at the start of the match and at the end of every turn since,
+    /// worked back from the ledger.
+    pub fn bank_history(&self, player: usize) -> Vec<i32> {
+        let mut balance = self.get_balance(player);
+        let mut history = vec![balance];
+        for turn in (1..=self.turn_number).rev() {
+            balance -= self
+                .ledger
+                .iter()
+                .filter(|t| t.player == player && t.turn_number == turn)
+                .map(|t| t.amount)
+                .sum::<i32>();
+            history.push(balance);
+        }
+        history.reverse();
+        history
+    }
+
+    pub fn chips_sold(&self, player: usize) -> usize {
+        self.ledger
+            .iter()
+            .filter(|t| t.player == player && t.kind == TransactionKind::ChipSale)
+            .count()
+    }
+
+    /// How many of each card `player` played, in order of first play.
+    pub fn card_usage(&self, player: usize) -> Vec<(CardType, usize)> {
+        let mut usage: Vec<(CardType, usize)> = vec![];
+        for transaction in self
+            .ledger
+            .iter()
+            .filter(|t| t.player == player && t.kind == TransactionKind::CardPurchase)
+        {
+            let Some(card_type) = transaction.card else {
+                continue;
+            };
+            match usage.iter_mut().find(|(played, _)| *played == card_type) {
+                Some((_, count)) => *count += 1,
+                None => usage.push((card_type, 1)),
+            }
+        }
+        usage
+    }
+}
+
+/// Takes chips from each product's stack in turn, so that one product
+/// doesn't hog a whole transport or sales card.
+fn interleave_stacks(stacks: Vec<Vec<(Entity, ChipType)>>) -> Vec<(Entity, ChipType)> {
+    let longest = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut chips = vec![];
+    for i in 0..longest {
+        for stack in &stacks {
+            if let Some(chip) = stack.get(i) {
+                chips.push(*chip);
+            }
+        }
+    }
+    chips
 }
 
 // Events
@@ -289,6 +913,31 @@ pub struct DiscardChip {
     pub entity: Entity,
 }
 
+/// Sent for each chip sold, after it has been paid for.
+#[derive(Debug, Event)]
+pub struct ChipSold {
+    /// The chip, still on the table; it's removed once it has flown off.
+    pub entity: Entity,
+    pub player: usize,
+    pub chip_type: ChipType,
+    /// The sales card that sold it.
+    pub card_type: CardType,
+    /// What the player was paid for it.
+    pub price: i32,
+}
+
+#[derive(Event)]
+pub struct TurnEnded {
+    pub player: usize,
+}
+
+/// A card press that broke the rules, so the player can be told why.
+#[derive(Event)]
+pub struct CardRejected {
+    pub card_entity: Entity,
+    pub blocker: PlayBlocker,
+}
+
 #[derive(Event)]
 pub struct GameOver {
     pub player_won: usize,
@@ -302,9 +951,16 @@ pub(super) fn plugin(app: &mut App) {
         .add_event::<DropChip>()
         .add_event::<MoveChip>()
         .add_event::<DiscardChip>()
+        .add_event::<ChipSold>()
         .add_event::<SwitchPlayer>()
+        .add_event::<TurnEnded>()
+        .add_event::<CardRejected>()
         .add_event::<GameOver>()
-        .insert_resource(PhaseTimer(Timer::from_seconds(0.3, TimerMode::Once)))
+        .insert_resource(PhaseTimer(Timer::from_seconds(
+            PHASE_DELAY_SECONDS,
+            TimerMode::Once,
+        )))
+        .insert_resource(GameRng::new(rand::random()))
         .add_systems(
             Update,
             (
@@ -314,7 +970,8 @@ pub(super) fn plugin(app: &mut App) {
                 handle_move_chip,
                 handle_switch_player,
                 check_game_over_conditions,
-            ),
+            )
+                .run_if(in_state(Pause::Running)),
         );
 }
 
@@ -329,34 +986,57 @@ pub fn apply_card_effects(
     mut ew_place_card_on_table: EventWriter<PlaceCardOnTable>,
     mut ew_drop_chip: EventWriter<DropChip>,
     mut ew_move_chip: EventWriter<MoveChip>,
-    mut ew_discard_chip: EventWriter<DiscardChip>,
+    mut ew_seize_chip: EventWriter<SeizeChip>,
+    mut ew_chip_sold: EventWriter<ChipSold>,
     mut ew_advance_phase: EventWriter<AdvancePhase>,
+    mut rng: ResMut<GameRng>,
 ) {
     let player = state.player;
 
-    // Apply Cards in Play Area
-    for (entity, card, _) in cards_on_table
+    let chips_in_area = |marker: usize| {
+        chips_on_table
+            .iter()
+            .filter(|(_, _, _, area)| area.player == player && area.marker == marker)
+            .count()
+    };
+    let mut chips_in_production = chips_in_area(1);
+    let mut chips_in_transport = chips_in_area(2);
+    // Chips already shipped or sold by an earlier card this call; their area
+    // only changes once the commands are applied.
+    let mut claimed: HashSet<Entity> = HashSet::new();
+
+    // Apply Cards in Play Area, left to right
+    let mut cards_in_play: Vec<_> = cards_on_table
         .iter()
         .filter(|(_, _, card_on_table)| card_on_table.player == player)
-    {
+        .collect();
+    cards_in_play.sort_by_key(|(_, _, card_on_table)| card_on_table.marker);
+    let product_in_slot = |marker: usize| {
+        cards_in_play
+            .iter()
+            .find(|(_, _, card_on_table)| card_on_table.marker == marker)
+            .and_then(|(_, card, _)| match card.data.card_type {
+                CardType::Production(chip_type) => Some(chip_type),
+                _ => None,
+            })
+    };
+
+    for (entity, card, card_on_table) in cards_in_play.iter().copied() {
         match state.phase {
             TurnPhase::ApplyProductionCards => match card.data.card_type {
-                CardType::Cocaine | CardType::Cannabis => {
-                    let production_power = 5;
-                    let discount = state.get_effects(player).iter().fold(0, |acc, effect| {
-                        match effect.effect_type {
-                            EffectType::Attack => acc + 1,
-                            EffectType::Drought => acc + 1,
+                CardType::Production(chip_type) => {
+                    let production_power =
+                        state.modify_stat(player, Stat::Production, PRODUCTION_PER_CARD);
+
+                    for _ in 0..production_power {
+                        // Whatever doesn't fit in the warehouse is lost.
+                        if chips_in_production >= state.get_storage_capacity(player, 1) {
+                            break;
                         }
-                    });
+                        chips_in_production += 1;
 
-                    for _ in 0..(production_power - discount).max(0) {
                         let event = DropChip {
-                            chip_type: match card.data.card_type {
-                                CardType::Cocaine => ChipType::Cocaine,
-                                CardType::Cannabis => ChipType::Cannabis,
-                                _ => ChipType::Cocaine,
-                            },
+                            chip_type,
                             area: 1,
                             player,
                         };
@@ -365,193 +1045,188 @@ pub fn apply_card_effects(
 
                     ew_place_card_off_table.send(PlaceCardOffTable {
                         card_entity: entity,
-                        deck_marker: 1,
+                        deck_marker: state.discard_marker(player),
                     });
                 }
                 _ => {}
             },
             TurnPhase::ApplyTransportationCards => match card.data.card_type {
                 CardType::Truck | CardType::Train => {
-                    let mut cannabis_chips_on_table = chips_on_table
-                        .iter()
-                        .filter(|(_, _, chip, area)| {
-                            chip.data == ChipType::Cannabis
-                                && area.marker == 1
-                                && area.player == player
-                                && chip.turn_activation_1 < state.turn_number
+                    let mut stacks: Vec<Vec<(Entity, ChipType)>> = ChipType::all()
+                        .map(|chip_type| {
+                            let mut stack = chips_on_table
+                                .iter()
+                                .filter(|(entity, _, chip, area)| {
+                                    !claimed.contains(entity)
+                                        && chip.data == chip_type
+                                        && area.marker == 1
+                                        && area.player == player
+                                        && chip.turn_activation_1 < state.turn_number
+                                })
+                                .collect::<Vec<_>>();
+                            stack.sort_by(|(_, t1, _, _), (_, t2, _, _)| {
+                                t1.translation.z.partial_cmp(&t2.translation.z).unwrap()
+                            });
+                            stack
+                                .iter()
+                                .map(|(entity, _, _, _)| (*entity, chip_type))
+                                .collect()
                         })
-                        .collect::<Vec<_>>();
-                    cannabis_chips_on_table.sort_by(|(_, t1, _, _), (_, t2, _, _)| {
-                        t1.translation.z.partial_cmp(&t2.translation.z).unwrap()
-                    });
-
-                    let mut cocaine_chips_on_table = chips_on_table
-                        .iter()
-                        .filter(|(_, _, chip, area)| {
-                            chip.data == ChipType::Cocaine
-                                && area.marker == 1
-                                && area.player == player
-                                && chip.turn_activation_1 < state.turn_number
-                        })
-                        .collect::<Vec<_>>();
-
-                    cocaine_chips_on_table.sort_by(|(_, t1, _, _), (_, t2, _, _)| {
-                        t1.translation.z.partial_cmp(&t2.translation.z).unwrap()
-                    });
-
-                    let mut entities_to_move: Vec<Entity> = vec![];
-                    let mut chip_value = match card.data.card_type {
-                        CardType::Truck => 20,
-                        CardType::Train => 50,
-                        _ => 0,
-                    };
-
-                    let common_chips =
-                        std::cmp::min(cannabis_chips_on_table.len(), cocaine_chips_on_table.len());
-
-                    for i in 0..common_chips {
-                        let cannabis_chip = cannabis_chips_on_table[i].0;
-                        let cocaine_chip = cocaine_chips_on_table[i].0;
-
-                        entities_to_move.push(cannabis_chip);
-                        entities_to_move.push(cocaine_chip);
-                    }
+                        .collect();
+                    // The production card in the slot to the left is loaded first.
+                    let first = product_in_slot(card_on_table.marker.saturating_sub(1))
+                        .map(|chip_type| std::mem::take(&mut stacks[chip_type.0]))
+                        .unwrap_or_default();
+                    let chips_to_move: Vec<_> =
+                        first.into_iter().chain(interleave_stacks(stacks)).collect();
+
+                    let mut chip_value = state.modify_stat(
+                        player,
+                        Stat::TransportCapacity,
+                        card.data.card_type.capacity(),
+                    );
 
-                    if common_chips < cannabis_chips_on_table.len() {
-                        for chip in cannabis_chips_on_table.iter().skip(common_chips) {
-                            entities_to_move.push(chip.0);
+                    let mut shipment: Vec<Entity> = vec![];
+                    for (entity, chip_type) in chips_to_move {
+                        if chips_in_transport >= state.get_storage_capacity(player, 2) {
+                            break;
                         }
-                    }
-
-                    if common_chips < cocaine_chips_on_table.len() {
-                        for chip in cocaine_chips_on_table.iter().skip(common_chips) {
-                            entities_to_move.push(chip.0);
+                        let weight = chip_type.product().weight;
+                        if chip_value < weight {
+                            continue;
                         }
+                        chips_in_transport += 1;
+                        claimed.insert(entity);
+                        shipment.push(entity);
+                        chip_value -= weight;
                     }
 
-                    for entity in entities_to_move {
-                        if chip_value <= 0 {
-                            break;
+                    let chance =
+                        interception_chance(&state, player, card.data.card_type, shipment.len());
+                    if !shipment.is_empty() && rng.rng.gen::<f32>() < chance {
+                        for entity in shipment {
+                            ew_seize_chip.send(SeizeChip { entity, player });
+                        }
+                    } else {
+                        for entity in shipment {
+                            let event = MoveChip {
+                                entity,
+                                area: 2,
+                                player,
+                            };
+                            ew_move_chip.send(event);
                         }
-
-                        let event = MoveChip {
-                            entity,
-                            area: 2,
-                            player,
-                        };
-                        ew_move_chip.send(event);
-                        chip_value -= 2;
                     }
 
                     ew_place_card_off_table.send(PlaceCardOffTable {
                         card_entity: entity,
-                        deck_marker: 1,
+                        deck_marker: state.discard_marker(player),
                     });
                 }
                 _ => {}
             },
             TurnPhase::ApplySalesCards => match card.data.card_type {
                 CardType::Export | CardType::LocalMarket => {
-                    let mut cannabis_chips_on_table = chips_on_table
-                        .iter()
-                        .filter(|(_, _, chip, area)| {
-                            chip.data == ChipType::Cannabis
-                                && area.marker == 2
-                                && area.player == player
-                                && chip.turn_activation_2 < state.turn_number
-                                && chip.turn_activation_2 != 0
-                        })
-                        .collect::<Vec<_>>();
-                    cannabis_chips_on_table.sort_by(|(_, t1, _, _), (_, t2, _, _)| {
-                        t2.translation.z.partial_cmp(&t1.translation.z).unwrap()
-                    });
-
-                    let mut cocaine_chips_on_table = chips_on_table
-                        .iter()
-                        .filter(|(_, _, chip, area)| {
-                            chip.data == ChipType::Cocaine
-                                && area.marker == 2
-                                && area.player == player
-                                && chip.turn_activation_2 < state.turn_number
-                                && chip.turn_activation_2 != 0
+                    let stacks = ChipType::all()
+                        .map(|chip_type| {
+                            let mut stack = chips_on_table
+                                .iter()
+                                .filter(|(entity, _, chip, area)| {
+                                    !claimed.contains(entity)
+                                        && chip.data == chip_type
+                                        && area.marker == 2
+                                        && area.player == player
+                                        && chip.turn_activation_2 < state.turn_number
+                                        && chip.turn_activation_2 != 0
+                                })
+                                .collect::<Vec<_>>();
+                            stack.sort_by(|(_, t1, _, _), (_, t2, _, _)| {
+                                t2.translation.z.partial_cmp(&t1.translation.z).unwrap()
+                            });
+                            stack
+                                .iter()
+                                .map(|(entity, _, _, _)| (*entity, chip_type))
+                                .collect()
                         })
-                        .collect::<Vec<_>>();
-
-                    cocaine_chips_on_table.sort_by(|(_, t1, _, _), (_, t2, _, _)| {
-                        t2.translation.z.partial_cmp(&t1.translation.z).unwrap()
-                    });
-
-                    let mut entities_to_discard: Vec<Entity> = vec![];
-                    let mut chip_value = match card.data.card_type {
-                        CardType::Export => 50,
-                        CardType::LocalMarket => 20,
-                        _ => 0,
-                    };
-
-                    let common_chips =
-                        std::cmp::min(cannabis_chips_on_table.len(), cocaine_chips_on_table.len());
+                        .collect();
+                    let chips_to_discard = interleave_stacks(stacks);
 
-                    for i in 0..common_chips {
-                        let cannabis_chip = cannabis_chips_on_table[i].0;
-                        let cocaine_chip = cocaine_chips_on_table[i].0;
+                    let mut chip_value = card.data.card_type.capacity();
 
-                        entities_to_discard.push(cannabis_chip);
-                        entities_to_discard.push(cocaine_chip);
-                    }
-
-                    if common_chips < cannabis_chips_on_table.len() {
-                        for i in common_chips..cannabis_chips_on_table.len() {
-                            entities_to_discard.push(cannabis_chips_on_table[i].0);
+                    for (entity, chip_type) in chips_to_discard {
+                        let weight = chip_type.product().weight;
+                        if chip_value < weight {
+                            continue;
                         }
-                    }
 
-                    if common_chips < cocaine_chips_on_table.len() {
-                        for i in common_chips..cocaine_chips_on_table.len() {
-                            entities_to_discard.push(cocaine_chips_on_table[i].0);
-                        }
-                    }
+                        chip_value -= weight;
+                        claimed.insert(entity);
 
-                    for entity in entities_to_discard {
-                        if chip_value <= 0 {
-                            break;
+                        let mut price =
+                            state.modify_stat(player, Stat::SalePrice, chip_type.price());
+                        if card.data.card_type == CardType::LocalMarket {
+                            price = state.modify_stat(player, Stat::LocalMarketPrice, price);
                         }
-
-                        let event = DiscardChip { entity };
-
-                        ew_discard_chip.send(event);
-                        chip_value -= 2;
-
-                        let cost: i32 = match card.data.card_type {
-                            CardType::Cocaine => 800,
-                            CardType::Cannabis => 400,
-                            _ => 0,
-                        };
-
-                        state.change_balance(player, cost);
+                        state.change_balance(
+                            player,
+                            price,
+                            TransactionKind::ChipSale,
+                            Some(card.data.card_type),
+                        );
+                        ew_chip_sold.send(ChipSold {
+                            entity,
+                            player,
+                            chip_type,
+                            card_type: card.data.card_type,
+                            price,
+                        });
                     }
+                    state.fire_trigger(player, Trigger::OnSale);
 
                     ew_place_card_off_table.send(PlaceCardOffTable {
                         card_entity: entity,
-                        deck_marker: 1,
+                        deck_marker: state.discard_marker(player),
                     });
                 }
                 _ => {}
             },
             TurnPhase::ApplyActionCards => match card.data.card_type {
+                CardType::Bribe => {
+                    state.add_effect(EffectType::Bribe, player, Some(CardType::Bribe));
+
+                    ew_place_card_off_table.send(PlaceCardOffTable {
+                        card_entity: entity,
+                        deck_marker: state.discard_marker(player),
+                    });
+                }
+                CardType::Warehouse => {
+                    state.warehouses[player - 1] += 1;
+
+                    ew_place_card_off_table.send(PlaceCardOffTable {
+                        card_entity: entity,
+                        deck_marker: state.discard_marker(player),
+                    });
+                }
+                CardType::Influence => {
+                    state.pending_influence[player - 1] += 1;
+
+                    ew_place_card_off_table.send(PlaceCardOffTable {
+                        card_entity: entity,
+                        deck_marker: state.discard_marker(player),
+                    });
+                }
                 CardType::Attack => {
-                    state.add_effect(
-                        EffectType::Attack,
-                        2,
-                        match player {
-                            1 => 2,
-                            2 => 1,
-                            _ => 1,
-                        },
-                    );
+                    let target = match player {
+                        1 => 2,
+                        2 => 1,
+                        _ => 1,
+                    };
+                    state.fire_trigger(target, Trigger::OnAttacked);
+                    state.add_effect(EffectType::Attack, target, Some(CardType::Attack));
 
                     ew_place_card_off_table.send(PlaceCardOffTable {
                         card_entity: entity,
-                        deck_marker: 1,
+                        deck_marker: state.discard_marker(player),
                     });
                 }
                 _ => {}
@@ -609,7 +1284,10 @@ pub fn apply_card_effects(
                 let card_type = card.data.card_type;
                 match card_type {
                     CardType::Drought => {
-                        state.add_effect(EffectType::Drought, 3, player);
+                        state.add_effect(EffectType::Drought, player, Some(CardType::Drought));
+                    }
+                    CardType::BigDeal => {
+                        state.add_effect(EffectType::BigDeal, player, Some(CardType::BigDeal));
                     }
 
                     _ => {}
@@ -641,6 +1319,7 @@ pub fn handle_next_phase(
     mut ew_align_chips_on_table: EventWriter<AlignChipsOnTable<ChipType>>,
     mut game_state: ResMut<GameState>,
     mut ew_switch_player: EventWriter<SwitchPlayer>,
+    mut ew_turn_ended: EventWriter<TurnEnded>,
     mut phase_timer: ResMut<PhaseTimer>,
     time: Res<Time>,
 ) {
@@ -661,62 +1340,22 @@ pub fn handle_next_phase(
             }
 
             TurnPhase::End => {
-                ew_align_chips_on_table.send(AlignChipsOnTable::<ChipType> {
-                    chip_area: ChipArea {
-                        marker: 1,
-                        player: 1,
-                    },
-                    chip_type: ChipType::Cocaine,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable::<ChipType> {
-                    chip_area: ChipArea {
-                        marker: 1,
-                        player: 1,
-                    },
-                    chip_type: ChipType::Cannabis,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable {
-                    chip_area: ChipArea {
-                        marker: 2,
-                        player: 1,
-                    },
-                    chip_type: ChipType::Cocaine,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable {
-                    chip_area: ChipArea {
-                        marker: 2,
-                        player: 1,
-                    },
-                    chip_type: ChipType::Cannabis,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable::<ChipType> {
-                    chip_area: ChipArea {
-                        marker: 1,
-                        player: 2,
-                    },
-                    chip_type: ChipType::Cocaine,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable::<ChipType> {
-                    chip_area: ChipArea {
-                        marker: 1,
-                        player: 2,
-                    },
-                    chip_type: ChipType::Cannabis,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable {
-                    chip_area: ChipArea {
-                        marker: 2,
-                        player: 2,
-                    },
-                    chip_type: ChipType::Cocaine,
-                });
-                ew_align_chips_on_table.send(AlignChipsOnTable {
-                    chip_area: ChipArea {
-                        marker: 2,
-                        player: 2,
-                    },
-                    chip_type: ChipType::Cannabis,
+                ew_turn_ended.send(TurnEnded {
+                    player: game_state.player,
                 });
+                let player = game_state.player;
+                game_state.tick_player_turn_effects(player);
+
+                for player in 1..=game_state.num_players {
+                    for marker in 1..=2 {
+                        for chip_type in ChipType::all() {
+                            ew_align_chips_on_table.send(AlignChipsOnTable::<ChipType> {
+                                chip_area: ChipArea { marker, player },
+                                chip_type,
+                            });
+                        }
+                    }
+                }
             }
             _ => {}
         }
@@ -726,6 +1365,7 @@ pub fn handle_next_phase(
         let next_player = game_state.player;
 
         if previous_player != next_player {
+            game_state.fire_trigger(next_player, Trigger::OnTurnStart);
             ew_switch_player.send(SwitchPlayer {
                 player: next_player,
             });
@@ -734,9 +1374,12 @@ pub fn handle_next_phase(
     }
 }
 
-pub fn handle_drop_chip(mut er_drop_chip: EventReader<DropChip>) {
-    for _drop_chip in er_drop_chip.read() {
-        // println!("Dropping chip: {:?}", drop_chip.chip_type);
+pub fn handle_drop_chip(
+    mut er_drop_chip: EventReader<DropChip>,
+    mut game_state: ResMut<GameState>,
+) {
+    for drop_chip in er_drop_chip.read() {
+        game_state.chips_produced[drop_chip.player - 1] += 1;
     }
 }
 
@@ -754,60 +1397,87 @@ pub fn handle_move_chip(
 pub fn handle_switch_player(
     mut er_switch_player: EventReader<SwitchPlayer>,
     mut game_state: ResMut<GameState>,
-    mut query: Query<(&mut Transform, &GameCamera)>,
 ) {
     for event in er_switch_player.read() {
         game_state.player = event.player;
-
-        for (mut transform, _) in query.iter_mut() {
-            if game_state.player == 1 {
-                *transform = Transform::from_xyz(0.0, 12.0, 15.0).looking_at(Vec3::ZERO, Vec3::Y);
-            } else {
-                *transform = Transform::from_xyz(-3.0, 12.0, -15.0)
-                    .looking_at(Vec3::ZERO + Vec3::new(-3.0, 0.0, 0.0), Vec3::Y);
-            }
-        }
     }
 }
 
 pub fn check_game_over_conditions(
     mut er_advance_phase: EventReader<AdvancePhase>,
-    game_state: Res<GameState>,
+    mut game_state: ResMut<GameState>,
     mut ew_game_over_event: EventWriter<GameOver>,
 ) {
-    for _ in er_advance_phase.read() {
-        let funds_per_player: Vec<i32> = (1..=game_state.num_players)
-            .map(|player| game_state.get_balance(player))
-            .collect();
+    if er_advance_phase.read().count() == 0 || game_state.winner.is_some() {
+        return;
+    }
 
-        let lowest_funds = *funds_per_player.iter().min().unwrap();
+    let funds_per_player: Vec<i32> = (1..=game_state.num_players)
+        .map(|player| game_state.get_balance(player))
+        .collect();
 
-        let highest_funds = *funds_per_player.iter().max().unwrap();
-        let index_highest_funds = funds_per_player
-            .iter()
-            .position(|&r| r == highest_funds)
-            .unwrap();
-        let total_players = game_state.num_players;
+    let highest_funds = *funds_per_player.iter().max().unwrap();
+    let index_highest_funds = funds_per_player
+        .iter()
+        .position(|&r| r == highest_funds)
+        .unwrap();
+    let solvent_players: Vec<usize> = (1..=game_state.num_players)
+        .filter(|player| !game_state.is_bankrupt(*player))
+        .collect();
+
+    let player_won = if solvent_players.len() == 1 {
+        solvent_players[0]
+    } else if highest_funds >= game_state.target_bank
+        || game_state.turn_number >= game_state.turn_limit
+    {
+        index_highest_funds + 1
+    } else {
+        return;
+    };
 
-        if total_players == 2 && lowest_funds <= 0 {
-            ew_game_over_event.send(GameOver {
-                player_won: index_highest_funds + 1,
-            });
-            return;
-        }
+    if let Some(event) = game_state.finish(player_won) {
+        ew_game_over_event.send(event);
+    }
+}
 
-        if highest_funds >= 50000 {
-            ew_game_over_event.send(GameOver {
-                player_won: index_highest_funds + 1,
-            });
-            return;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if game_state.turn_number >= 20 {
-            ew_game_over_event.send(GameOver {
-                player_won: index_highest_funds + 1,
-            });
-            return;
+    /// Ends the current player's turn the way `handle_next_phase` does.
+    fn end_turn(state: &mut GameState) {
+        state.phase = TurnPhase::End;
+        let player = state.player;
+        state.tick_player_turn_effects(player);
+        state.advance();
+    }
+
+    fn has_drought(state: &GameState, player: usize) -> bool {
+        state
+            .get_effects(player)
+            .iter()
+            .any(|effect| effect.effect_type == EffectType::Drought)
+    }
+
+    #[test]
+    fn drought_counts_down_in_the_affected_players_turns() {
+        let EffectDuration::PlayerTurns(turns) = EffectType::Drought.definition().duration else {
+            panic!("Drought should last a number of player turns");
+        };
+        let mut state = GameState::new(2);
+        state.add_effect(EffectType::Drought, 2, None);
+
+        // Player 1's turns don't count towards it.
+        end_turn(&mut state);
+        assert!(has_drought(&state, 2));
+        for _ in 1..turns {
+            end_turn(&mut state);
+            assert!(has_drought(&state, 2));
+            end_turn(&mut state);
+            assert!(has_drought(&state, 2));
         }
+
+        end_turn(&mut state);
+        assert!(!has_drought(&state, 2));
     }
 }
@@ -0,0 +1,40 @@
+//! Personal decks. Each player draws from their own deck, played cards go to
+//! their discard pile, and the pile is shuffled back in once the deck can no
+//! longer fill a hand.
+
+use bevy::prelude::*;
+use bevy_la_mesa::{events::DeckShuffle, Card, Deck, LaMesaPluginSettings};
+
+use super::cards::{GameState, Kard, TurnEnded};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, recycle_discard_pile);
+}
+
+fn recycle_discard_pile(
+    mut er_turn_ended: EventReader<TurnEnded>,
+    mut ew_shuffle: EventWriter<DeckShuffle>,
+    mut cards: Query<&mut Deck, With<Card<Kard>>>,
+    state: Res<GameState>,
+    plugin_settings: Res<LaMesaPluginSettings>,
+) {
+    for event in er_turn_ended.read() {
+        let deck_marker = state.deck_marker(event.player);
+        let discard_marker = state.discard_marker(event.player);
+
+        let cards_in_deck = cards
+            .iter()
+            .filter(|deck| deck.marker == deck_marker)
+            .count();
+        if cards_in_deck >= plugin_settings.hand_size {
+            continue;
+        }
+
+        for mut deck in cards.iter_mut() {
+            if deck.marker == discard_marker {
+                deck.marker = deck_marker;
+            }
+        }
+        ew_shuffle.send(DeckShuffle { deck_marker });
+    }
+}
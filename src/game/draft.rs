@@ -0,0 +1,81 @@
+//! Draft variant for the opening. The playing deck is split into packs; each
+//! player picks a card from the pack in front of them and passes the rest on,
+//! building their own deck one card at a time.
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use super::cards::{GameRng, Kard};
+
+/// Packs each player opens over the draft.
+pub const PACKS_PER_PLAYER: usize = 2;
+
+#[derive(Resource)]
+pub struct Draft {
+    /// Pack in front of each player, indexed by player.
+    pub packs: Vec<Vec<Kard>>,
+    unopened: Vec<Vec<Kard>>,
+    /// Cards picked so far, indexed by player.
+    pub picks: Vec<Vec<Kard>>,
+    /// Player whose pick it is.
+    pub player: usize,
+}
+
+impl Draft {
+    pub fn new(mut pool: Vec<Kard>, num_players: usize, rng: &mut GameRng) -> Self {
+        pool.shuffle(&mut rng.rng);
+
+        let num_packs = num_players * PACKS_PER_PLAYER;
+        let pack_size = pool.len() / num_packs;
+        let mut unopened: Vec<Vec<Kard>> = pool
+            .chunks(pack_size.max(1))
+            .take(num_packs)
+            .map(|pack| pack.to_vec())
+            .collect();
+        let packs = unopened.drain(..num_players.min(unopened.len())).collect();
+
+        Self {
+            packs,
+            unopened,
+            picks: vec![vec![]; num_players],
+            player: 1,
+        }
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.picks.len()
+    }
+
+    /// Takes a card from the current player's pack. Once everyone has picked,
+    /// the packs are passed to the next player, or new ones are opened when
+    /// they run out.
+    pub fn pick(&mut self, index: usize) {
+        let pack = &mut self.packs[self.player - 1];
+        if index >= pack.len() {
+            return;
+        }
+        let card = pack.remove(index);
+        self.picks[self.player - 1].push(card);
+
+        if self.player < self.num_players() {
+            self.player += 1;
+            return;
+        }
+
+        self.player = 1;
+        self.packs.rotate_right(1);
+        if self.packs.iter().all(Vec::is_empty) && !self.unopened.is_empty() {
+            let num_players = self.num_players().min(self.unopened.len());
+            self.packs = self.unopened.drain(..num_players).collect();
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.packs.iter().all(Vec::is_empty) && self.unopened.is_empty()
+    }
+}
+
+/// Decks built in the draft, indexed by player. Used by the next board that
+/// gets spawned.
+#[derive(Resource)]
+pub struct DraftedDecks(pub Vec<Vec<Kard>>);
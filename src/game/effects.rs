@@ -0,0 +1,182 @@
+//! Lasting effects on players. Each [`EffectType`] declares the stats it
+//! modifies, how repeated applications stack, how long it lasts and what it
+//! does when certain things happen to the affected player.
+
+use serde::Deserialize;
+
+use super::cards::CardType;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EffectType {
+    Drought,
+    Attack,
+    Bribe,
+    BigDeal,
+}
+
+/// Numbers the rules read through [`GameState::modify_stat`](super::cards::GameState::modify_stat).
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum Stat {
+    /// Chips dropped per production card.
+    Production,
+    /// Weight a transport card can carry.
+    TransportCapacity,
+    /// Payout per chip sold.
+    SalePrice,
+    /// Payout per chip sold through a Local Market, on top of `SalePrice`.
+    LocalMarketPrice,
+    /// Price of playing a card from hand.
+    CardCost,
+    /// Chance of a shipment being intercepted, in percent.
+    InterceptionRisk,
+    /// Turns chips can wait in transport before they're seized.
+    SeizureTurns,
+}
+
+impl Stat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::Production => "production",
+            Stat::TransportCapacity => "transport capacity",
+            Stat::SalePrice => "sale price",
+            Stat::LocalMarketPrice => "local market price",
+            Stat::CardCost => "card cost",
+            Stat::InterceptionRisk => "interception risk",
+            Stat::SeizureTurns => "turns before seizure",
+        }
+    }
+}
+
+/// Flat modifiers are summed first, then percentages are applied on top.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum Modifier {
+    Add(Stat, i32),
+    Percent(Stat, i32),
+}
+
+impl Modifier {
+    pub fn describe(&self) -> String {
+        match self {
+            Modifier::Add(stat, amount) => format!("{:+} {}", amount, stat.name()),
+            Modifier::Percent(stat, percent) => format!("{:+}% {}", percent, stat.name()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stacking {
+    /// A new application replaces the running one and restarts its duration.
+    Replace,
+    /// Every application counts on its own.
+    Stack,
+    /// A new application is ignored while one is running.
+    KeepExisting,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EffectDuration {
+    /// Lasts until the end of the affected player's n-th turn, counting the
+    /// current one if it's theirs.
+    PlayerTurns(usize),
+    /// Lasts for n global turns, i.e. until every player has played n times.
+    Rounds(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    OnTurnStart,
+    OnSale,
+    OnAttacked,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TriggerAction {
+    /// Takes money from the affected player.
+    Charge(i32),
+    /// Ends the effect.
+    Expire,
+}
+
+pub struct EffectDefinition {
+    pub modifiers: &'static [Modifier],
+    pub stacking: Stacking,
+    pub duration: EffectDuration,
+    pub hooks: &'static [(Trigger, TriggerAction)],
+}
+
+impl EffectType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectType::Drought => "Drought",
+            EffectType::Attack => "Attack",
+            EffectType::Bribe => "Bribe",
+            EffectType::BigDeal => "Big Deal",
+        }
+    }
+
+    pub fn definition(&self) -> &'static EffectDefinition {
+        match self {
+            EffectType::Drought => &EffectDefinition {
+                modifiers: &[
+                    Modifier::Add(Stat::Production, -1),
+                    Modifier::Percent(Stat::CardCost, 10),
+                ],
+                stacking: Stacking::Replace,
+                duration: EffectDuration::PlayerTurns(3),
+                hooks: &[],
+            },
+            EffectType::Attack => &EffectDefinition {
+                modifiers: &[
+                    Modifier::Add(Stat::Production, -1),
+                    Modifier::Add(Stat::TransportCapacity, -4),
+                    Modifier::Add(Stat::InterceptionRisk, 15),
+                ],
+                stacking: Stacking::Stack,
+                duration: EffectDuration::PlayerTurns(2),
+                hooks: &[(Trigger::OnTurnStart, TriggerAction::Charge(200))],
+            },
+            EffectType::Bribe => &EffectDefinition {
+                modifiers: &[Modifier::Percent(Stat::InterceptionRisk, -50)],
+                stacking: Stacking::Replace,
+                duration: EffectDuration::PlayerTurns(2),
+                // A raid burns the contact.
+                hooks: &[(Trigger::OnAttacked, TriggerAction::Expire)],
+            },
+            EffectType::BigDeal => &EffectDefinition {
+                modifiers: &[Modifier::Percent(Stat::SalePrice, 50)],
+                stacking: Stacking::KeepExisting,
+                // The market moves for everyone, so the deal is gone by next round.
+                duration: EffectDuration::Rounds(1),
+                // Only the first sale of the turn gets the better price.
+                hooks: &[(Trigger::OnSale, TriggerAction::Expire)],
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Effect {
+    pub effect_type: EffectType,
+    pub player: usize,
+    pub turn_number: usize,
+    /// Turns of the affected player still to play, for
+    /// [`EffectDuration::PlayerTurns`] effects.
+    pub player_turns_left: usize,
+    pub source: Option<CardType>,
+}
+
+impl Effect {
+    /// Turns the effect will still be active for, counting the current one.
+    pub fn remaining_turns(&self, turn_number: usize) -> usize {
+        match self.effect_type.definition().duration {
+            EffectDuration::PlayerTurns(_) => self.player_turns_left,
+            EffectDuration::Rounds(rounds) => {
+                (self.turn_number + rounds).saturating_sub(turn_number)
+            }
+        }
+    }
+
+    pub fn is_expired(&self, turn_number: usize) -> bool {
+        self.remaining_turns(turn_number) == 0
+    }
+}
@@ -0,0 +1,703 @@
+//! Keyboard and gamepad play. Keys and gamepad buttons are mapped to
+//! [`GameAction`]s through remappable [`InputBindings`]. A focus cursor moves
+//! between the visible buttons and the cards in the active player's hand;
+//! confirming presses the button or plays the card, just like a click.
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, Enum},
+    ui::UiSystem,
+    utils::{HashMap, HashSet},
+};
+use bevy_la_mesa::{events::CardPress, Card, Hand};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ai::AiPlayers,
+    cards::{GameState, Kard},
+    preview::HoveredCard,
+    spawn::{level::SpawnBoard, ui::CardGameUIAction},
+    tutorial::Tutorial,
+};
+use crate::{screen::Screen, ui::palette::FOCUS_OUTLINE, GameCamera};
+
+/// Something the player can do with a single key or gamepad button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum GameAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    PreviousCard,
+    NextCard,
+    DrawHand,
+    ShuffleDeck,
+    AdvancePhase,
+    FocusOverview,
+    FocusHand,
+    FocusPlayArea,
+    FocusChips,
+    FocusOpponent,
+    Pause,
+    OrbitLeft,
+    OrbitRight,
+    TiltUp,
+    TiltDown,
+    PanLeft,
+    PanRight,
+    PanForward,
+    PanBack,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl GameAction {
+    pub const ALL: [GameAction; 26] = [
+        GameAction::Up,
+        GameAction::Down,
+        GameAction::Left,
+        GameAction::Right,
+        GameAction::Confirm,
+        GameAction::PreviousCard,
+        GameAction::NextCard,
+        GameAction::DrawHand,
+        GameAction::ShuffleDeck,
+        GameAction::AdvancePhase,
+        GameAction::FocusOverview,
+        GameAction::FocusHand,
+        GameAction::FocusPlayArea,
+        GameAction::FocusChips,
+        GameAction::FocusOpponent,
+        GameAction::Pause,
+        GameAction::OrbitLeft,
+        GameAction::OrbitRight,
+        GameAction::TiltUp,
+        GameAction::TiltDown,
+        GameAction::PanLeft,
+        GameAction::PanRight,
+        GameAction::PanForward,
+        GameAction::PanBack,
+        GameAction::ZoomIn,
+        GameAction::ZoomOut,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameAction::Up => "Move Up",
+            GameAction::Down => "Move Down",
+            GameAction::Left => "Move Left",
+            GameAction::Right => "Move Right",
+            GameAction::Confirm => "Press / Play Card",
+            GameAction::PreviousCard => "Previous Card",
+            GameAction::NextCard => "Next Card",
+            GameAction::DrawHand => "Draw Hand",
+            GameAction::ShuffleDeck => "Shuffle Deck",
+            GameAction::AdvancePhase => "Advance Phase",
+            GameAction::FocusOverview => "Camera: Overview",
+            GameAction::FocusHand => "Camera: Hand",
+            GameAction::FocusPlayArea => "Camera: Play Area",
+            GameAction::FocusChips => "Camera: Chips",
+            GameAction::FocusOpponent => "Camera: Opponent",
+            GameAction::Pause => "Pause",
+            GameAction::OrbitLeft => "Orbit Left",
+            GameAction::OrbitRight => "Orbit Right",
+            GameAction::TiltUp => "Tilt Up",
+            GameAction::TiltDown => "Tilt Down",
+            GameAction::PanLeft => "Pan Left",
+            GameAction::PanRight => "Pan Right",
+            GameAction::PanForward => "Pan Forward",
+            GameAction::PanBack => "Pan Back",
+            GameAction::ZoomIn => "Zoom In",
+            GameAction::ZoomOut => "Zoom Out",
+        }
+    }
+
+    /// Whether the action moves the camera for as long as it's held.
+    pub fn is_camera_move(&self) -> bool {
+        matches!(
+            self,
+            GameAction::OrbitLeft
+                | GameAction::OrbitRight
+                | GameAction::TiltUp
+                | GameAction::TiltDown
+                | GameAction::PanLeft
+                | GameAction::PanRight
+                | GameAction::PanForward
+                | GameAction::PanBack
+                | GameAction::ZoomIn
+                | GameAction::ZoomOut
+        )
+    }
+
+    fn default_binding(&self) -> Binding {
+        let (key, button) = match self {
+            GameAction::Up => (KeyCode::ArrowUp, Some(GamepadButtonType::DPadUp)),
+            GameAction::Down => (KeyCode::ArrowDown, Some(GamepadButtonType::DPadDown)),
+            GameAction::Left => (KeyCode::ArrowLeft, Some(GamepadButtonType::DPadLeft)),
+            GameAction::Right => (KeyCode::ArrowRight, Some(GamepadButtonType::DPadRight)),
+            GameAction::Confirm => (KeyCode::Enter, Some(GamepadButtonType::South)),
+            GameAction::PreviousCard => (KeyCode::KeyZ, Some(GamepadButtonType::LeftTrigger)),
+            GameAction::NextCard => (KeyCode::KeyX, Some(GamepadButtonType::RightTrigger)),
+            GameAction::DrawHand => (KeyCode::KeyC, Some(GamepadButtonType::West)),
+            GameAction::ShuffleDeck => (KeyCode::KeyV, Some(GamepadButtonType::North)),
+            GameAction::AdvancePhase => (KeyCode::Space, Some(GamepadButtonType::Start)),
+            GameAction::FocusOverview => (KeyCode::Digit1, Some(GamepadButtonType::RightThumb)),
+            GameAction::FocusHand => (KeyCode::Digit2, None),
+            GameAction::FocusPlayArea => (KeyCode::Digit3, None),
+            GameAction::FocusChips => (KeyCode::Digit4, None),
+            GameAction::FocusOpponent => (KeyCode::Digit5, None),
+            GameAction::Pause => (KeyCode::Escape, Some(GamepadButtonType::Select)),
+            // The sticks turn and pan the camera on a gamepad.
+            GameAction::OrbitLeft => (KeyCode::KeyQ, None),
+            GameAction::OrbitRight => (KeyCode::KeyE, None),
+            GameAction::TiltUp => (KeyCode::KeyR, None),
+            GameAction::TiltDown => (KeyCode::KeyF, None),
+            GameAction::PanLeft => (KeyCode::KeyA, None),
+            GameAction::PanRight => (KeyCode::KeyD, None),
+            GameAction::PanForward => (KeyCode::KeyW, None),
+            GameAction::PanBack => (KeyCode::KeyS, None),
+            GameAction::ZoomIn => (KeyCode::Equal, Some(GamepadButtonType::RightTrigger2)),
+            GameAction::ZoomOut => (KeyCode::Minus, Some(GamepadButtonType::LeftTrigger2)),
+        };
+        Binding {
+            key: Some(key),
+            button,
+        }
+    }
+
+    /// The Deck or Hand button this action stands in for.
+    fn shortcut(&self) -> Option<CardGameUIAction> {
+        match self {
+            GameAction::DrawHand => Some(CardGameUIAction::ButtonDrawHand),
+            GameAction::ShuffleDeck => Some(CardGameUIAction::ButtonShuffleDeck),
+            GameAction::AdvancePhase => Some(CardGameUIAction::ButtonAdvancePhase),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum Device {
+    Keyboard,
+    Gamepad,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Binding {
+    pub key: Option<KeyCode>,
+    pub button: Option<GamepadButtonType>,
+}
+
+#[derive(Resource, Clone, PartialEq, Eq)]
+pub struct InputBindings(pub HashMap<GameAction, Binding>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings(
+            GameAction::ALL
+                .into_iter()
+                .map(|action| (action, action.default_binding()))
+                .collect(),
+        )
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, action: GameAction) -> Binding {
+        self.0.get(&action).copied().unwrap_or_default()
+    }
+
+    /// Binds `key` to `action`, taking it away from any other action.
+    pub fn set_key(&mut self, action: GameAction, key: KeyCode) {
+        for (other, binding) in self.0.iter_mut() {
+            if *other != action && binding.key == Some(key) {
+                binding.key = None;
+            }
+        }
+        self.0.entry(action).or_default().key = Some(key);
+    }
+
+    /// Binds `button` to `action`, taking it away from any other action.
+    pub fn set_button(&mut self, action: GameAction, button: GamepadButtonType) {
+        for (other, binding) in self.0.iter_mut() {
+            if *other != action && binding.button == Some(button) {
+                binding.button = None;
+            }
+        }
+        self.0.entry(action).or_default().button = Some(button);
+    }
+
+    pub fn to_saved(&self) -> Vec<SavedBinding> {
+        GameAction::ALL
+            .into_iter()
+            .map(|action| {
+                let binding = self.get(action);
+                SavedBinding {
+                    action,
+                    key: binding.key.map(|key| key.variant_name().to_string()),
+                    button: binding
+                        .button
+                        .map(|button| button.variant_name().to_string()),
+                }
+            })
+            .collect()
+    }
+
+    /// The default bindings, overridden by the saved ones.
+    pub fn from_saved(saved: &[SavedBinding]) -> Self {
+        let saved: Vec<(GameAction, Binding)> = saved
+            .iter()
+            .map(|saved| {
+                let binding = Binding {
+                    key: saved.key.as_deref().and_then(from_variant_name),
+                    button: saved.button.as_deref().and_then(from_variant_name),
+                };
+                (saved.action, binding)
+            })
+            .collect();
+
+        // Actions added since the bindings were saved keep their defaults,
+        // unless a saved binding has taken the key or button.
+        let mut bindings = InputBindings::default();
+        for (action, binding) in bindings.0.iter_mut() {
+            if saved.iter().any(|(saved_action, _)| saved_action == action) {
+                continue;
+            }
+            if saved
+                .iter()
+                .any(|(_, saved)| saved.key.is_some() && saved.key == binding.key)
+            {
+                binding.key = None;
+            }
+            if saved
+                .iter()
+                .any(|(_, saved)| saved.button.is_some() && saved.button == binding.button)
+            {
+                binding.button = None;
+            }
+        }
+        bindings.0.extend(saved);
+        bindings
+    }
+}
+
+/// A binding as written to storage, with keys and buttons by name.
+#[derive(Serialize, Deserialize)]
+pub struct SavedBinding {
+    pub action: GameAction,
+    pub key: Option<String>,
+    pub button: Option<String>,
+}
+
+/// Looks up a unit variant such as `KeyCode::KeyA` by its name.
+fn from_variant_name<T: FromReflect>(name: &str) -> Option<T> {
+    T::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+}
+
+pub fn key_name(key: Option<KeyCode>) -> String {
+    let Some(key) = key else {
+        return "-".to_string();
+    };
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+pub fn button_name(button: Option<GamepadButtonType>) -> String {
+    button.map_or("-".to_string(), |button| format!("{:?}", button))
+}
+
+/// Actions triggered this frame, and those held down.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    just_pressed: HashSet<GameAction>,
+    pressed: HashSet<GameAction>,
+}
+
+impl ActionState {
+    pub fn just_pressed(&self, action: GameAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn pressed(&self, action: GameAction) -> bool {
+        self.pressed.contains(&action)
+    }
+}
+
+/// Binding waiting for the next key or gamepad button. No actions fire
+/// while it is set.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<(GameAction, Device)>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Focus {
+    Button(Entity),
+    Card(Entity),
+}
+
+/// Where the keyboard and gamepad cursor is.
+#[derive(Resource, Default)]
+pub struct InputFocus {
+    pub current: Option<Focus>,
+    /// Button pressed from the keyboard or gamepad, released next frame.
+    pressed: Option<Entity>,
+    outlined: Option<Entity>,
+}
+
+/// A menu drawn over the game. While one is open, the cursor and shortcuts
+/// only reach the buttons inside it.
+#[derive(Component)]
+pub struct FocusScope;
+
+/// Marker floating over the focused hand card.
+#[derive(Component)]
+struct CardCursor;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InputBindings>()
+        .init_resource::<ActionState>()
+        .init_resource::<Rebinding>()
+        .init_resource::<InputFocus>()
+        .observe(spawn_card_cursor)
+        .add_systems(
+            PreUpdate,
+            (record_actions, move_focus, activate_focus, show_focus)
+                .chain()
+                .after(InputSystem)
+                .after(UiSystem::Focus),
+        );
+}
+
+fn spawn_card_cursor(
+    _trigger: Trigger<SpawnBoard>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cone {
+                radius: 0.25,
+                height: 0.5,
+            }),
+            material: materials.add(StandardMaterial {
+                base_color: FOCUS_OUTLINE,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        CardCursor,
+        StateScoped(Screen::Playing),
+        Name::new("Card Cursor"),
+    ));
+}
+
+fn record_actions(
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut actions: ResMut<ActionState>,
+) {
+    actions.just_pressed.clear();
+    actions.pressed.clear();
+    if rebinding.0.is_some() {
+        return;
+    }
+
+    for (action, binding) in bindings.0.iter() {
+        let key = binding.key.is_some_and(|key| keys.just_pressed(key));
+        let button = binding.button.is_some_and(|button_type| {
+            gamepad_buttons
+                .get_just_pressed()
+                .any(|button| button.button_type == button_type)
+        });
+        if key || button {
+            actions.just_pressed.insert(*action);
+        }
+
+        let key_held = binding.key.is_some_and(|key| keys.pressed(key));
+        let button_held = binding.button.is_some_and(|button_type| {
+            gamepad_buttons
+                .get_pressed()
+                .any(|button| button.button_type == button_type)
+        });
+        if key_held || button_held {
+            actions.pressed.insert(*action);
+        }
+    }
+}
+
+/// Buttons the cursor can land on, with what they do on the table if
+/// anything.
+type FocusButtons<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Node,
+        &'static GlobalTransform,
+        &'static InheritedVisibility,
+        Option<&'static CardGameUIAction>,
+    ),
+    With<Button>,
+>;
+
+/// Everything the cursor can land on, with its position on screen. During the
+/// tutorial only what the current step allows.
+fn focus_targets(
+    screen: &State<Screen>,
+    state: &GameState,
+    ai_players: &AiPlayers,
+    tutorial: Option<&Tutorial>,
+    buttons: &FocusButtons,
+    cards: &Query<(Entity, &Hand, &GlobalTransform), With<Card<Kard>>>,
+    camera: &Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    scopes: &Query<Entity, With<FocusScope>>,
+    parents: &Query<&Parent>,
+) -> Vec<(Focus, Vec2)> {
+    let in_scope = |entity: Entity| {
+        scopes.is_empty()
+            || parents
+                .iter_ancestors(entity)
+                .any(|ancestor| scopes.contains(ancestor))
+    };
+    let mut targets: Vec<(Focus, Vec2)> = buttons
+        .iter()
+        .filter(|(entity, node, _, visibility, action)| {
+            visibility.get()
+                && node.size() != Vec2::ZERO
+                && in_scope(*entity)
+                && action.map_or(true, |action| {
+                    tutorial.map_or(true, |tutorial| tutorial.allows(action))
+                })
+        })
+        .map(|(entity, _, transform, _, _)| {
+            (Focus::Button(entity), transform.translation().truncate())
+        })
+        .collect();
+
+    let human_turn = !ai_players.0.contains(&state.player);
+    let cards_allowed = tutorial.map_or(true, |tutorial| tutorial.allows_card_press(false));
+    if *screen.get() == Screen::Playing && human_turn && cards_allowed && scopes.is_empty() {
+        if let Ok((camera, camera_transform)) = camera.get_single() {
+            targets.extend(
+                cards
+                    .iter()
+                    .filter(|(_, hand, _)| hand.player == state.player)
+                    .filter_map(|(entity, _, transform)| {
+                        let position =
+                            camera.world_to_viewport(camera_transform, transform.translation())?;
+                        Some((Focus::Card(entity), position))
+                    }),
+            );
+        }
+    }
+    targets
+}
+
+fn move_focus(
+    actions: Res<ActionState>,
+    mut focus: ResMut<InputFocus>,
+    mut hovered_card: ResMut<HoveredCard>,
+    screen: Res<State<Screen>>,
+    state: Res<GameState>,
+    ai_players: Res<AiPlayers>,
+    tutorial: Option<Res<Tutorial>>,
+    buttons: FocusButtons,
+    cards: Query<(Entity, &Hand, &GlobalTransform), With<Card<Kard>>>,
+    camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    scopes: Query<Entity, With<FocusScope>>,
+    parents: Query<&Parent>,
+) {
+    let targets = focus_targets(
+        &screen,
+        &state,
+        &ai_players,
+        tutorial.as_deref(),
+        &buttons,
+        &cards,
+        &camera,
+        &scopes,
+        &parents,
+    );
+    let previous = focus.current;
+    let current =
+        previous.and_then(|previous| targets.iter().find(|(focus, _)| *focus == previous));
+    let current_focus = current.map(|(focus, _)| *focus);
+
+    let mut hand: Vec<&(Focus, Vec2)> = targets
+        .iter()
+        .filter(|(focus, _)| matches!(focus, Focus::Card(_)))
+        .collect();
+    hand.sort_by(|(_, a), (_, b)| a.x.total_cmp(&b.x));
+
+    let step = |forward: bool| {
+        let index = hand
+            .iter()
+            .position(|(focus, _)| Some(*focus) == current_focus)
+            .map(|index| {
+                if forward {
+                    (index + 1) % hand.len()
+                } else {
+                    (index + hand.len() - 1) % hand.len()
+                }
+            })
+            .unwrap_or(0);
+        hand.get(index).map(|(focus, _)| *focus)
+    };
+
+    let mut next = current_focus;
+    for (action, direction) in [
+        (GameAction::Up, Vec2::NEG_Y),
+        (GameAction::Down, Vec2::Y),
+        (GameAction::Left, Vec2::NEG_X),
+        (GameAction::Right, Vec2::X),
+    ] {
+        if !actions.just_pressed(action) {
+            continue;
+        }
+        next = match current {
+            // Start on the first hand card, or the top button.
+            None => hand.first().map(|(focus, _)| *focus).or_else(|| {
+                targets
+                    .iter()
+                    .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+                    .map(|(focus, _)| *focus)
+            }),
+            // The closest target in that direction, favouring straight lines.
+            Some((_, from)) => targets
+                .iter()
+                .filter_map(|(focus, position)| {
+                    let offset = *position - *from;
+                    let along = offset.dot(direction);
+                    let across = (offset - direction * along).length();
+                    (along > 1.0).then_some((*focus, along + across * 2.0))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(focus, _)| focus)
+                .or(next),
+        };
+    }
+    if actions.just_pressed(GameAction::NextCard) {
+        next = step(true).or(next);
+    }
+    if actions.just_pressed(GameAction::PreviousCard) {
+        next = step(false).or(next);
+    }
+
+    if next == previous {
+        return;
+    }
+    // Show the preview for the focused card, as hovering would.
+    if let Some(Focus::Card(card)) = previous {
+        if hovered_card.0 == Some(card) {
+            hovered_card.0 = None;
+        }
+    }
+    if let Some(Focus::Card(card)) = next {
+        hovered_card.0 = Some(card);
+    }
+    focus.current = next;
+}
+
+fn activate_focus(
+    actions: Res<ActionState>,
+    mut focus: ResMut<InputFocus>,
+    mut buttons: Query<
+        (
+            Entity,
+            &mut Interaction,
+            &InheritedVisibility,
+            Option<&CardGameUIAction>,
+        ),
+        With<Button>,
+    >,
+    mut ew_card_press: EventWriter<CardPress>,
+    scopes: Query<(), With<FocusScope>>,
+) {
+    if let Some(entity) = focus.pressed.take() {
+        if let Ok((_, mut interaction, _, _)) = buttons.get_mut(entity) {
+            if *interaction == Interaction::Pressed {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+
+    let mut press = None;
+    if actions.just_pressed(GameAction::Confirm) {
+        match focus.current {
+            Some(Focus::Button(entity)) => press = Some(entity),
+            Some(Focus::Card(card_entity)) => {
+                ew_card_press.send(CardPress { card_entity });
+            }
+            None => {}
+        }
+    }
+    for action in GameAction::ALL {
+        let Some(shortcut) = action.shortcut() else {
+            continue;
+        };
+        if !actions.just_pressed(action) || !scopes.is_empty() {
+            continue;
+        }
+        press = buttons
+            .iter()
+            .find(|(_, _, visibility, ui_action)| visibility.get() && *ui_action == Some(&shortcut))
+            .map(|(entity, _, _, _)| entity)
+            .or(press);
+    }
+
+    if let Some(entity) = press {
+        if let Ok((_, mut interaction, _, _)) = buttons.get_mut(entity) {
+            *interaction = Interaction::Pressed;
+            focus.pressed = Some(entity);
+        }
+    }
+}
+
+fn show_focus(
+    mut commands: Commands,
+    mut focus: ResMut<InputFocus>,
+    cards: Query<&GlobalTransform, With<Card<Kard>>>,
+    mut cursor: Query<(&mut Transform, &mut Visibility), With<CardCursor>>,
+) {
+    let button = match focus.current {
+        Some(Focus::Button(entity)) => Some(entity),
+        _ => None,
+    };
+    if focus.outlined != button {
+        if let Some(mut entity) = focus
+            .outlined
+            .and_then(|entity| commands.get_entity(entity))
+        {
+            entity.remove::<Outline>();
+        }
+        if let Some(mut entity) = button.and_then(|entity| commands.get_entity(entity)) {
+            entity.insert(Outline::new(Val::Px(3.0), Val::Px(2.0), FOCUS_OUTLINE));
+        }
+        focus.outlined = button;
+    }
+
+    let Ok((mut transform, mut visibility)) = cursor.get_single_mut() else {
+        return;
+    };
+    let card = match focus.current {
+        Some(Focus::Card(entity)) => cards.get(entity).ok(),
+        _ => None,
+    };
+    match card {
+        Some(card) => {
+            transform.translation = card.translation() + Vec3::Y * 2.2;
+            transform.rotation = Quat::from_rotation_x(std::f32::consts::PI);
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
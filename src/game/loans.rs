@@ -0,0 +1,236 @@
+//! Lending, interest and bankruptcy.
+
+use bevy::prelude::*;
+use bevy_la_mesa::{Chip, ChipArea};
+
+use super::{
+    cards::{ChipType, DiscardChip, GameState, TransactionKind, TurnEnded},
+    warehouse::chip_expired,
+};
+
+/// Size of a single loan taken from the side panel.
+pub const LOAN_AMOUNT: i32 = 1000;
+/// Maximum outstanding debt per player.
+pub const CREDIT_LIMIT: i32 = 10000;
+/// Interest per turn on regular loans, in percent.
+pub const INTEREST_RATE: i32 = 5;
+/// Interest per turn on loans negotiated to cover a shortfall, in percent.
+pub const EMERGENCY_INTEREST_RATE: i32 = 15;
+/// Share of a chip's price lost when it's sold off to cover debt, in percent.
+pub const LIQUIDATION_DISCOUNT: i32 = 50;
+
+#[derive(Event)]
+pub struct TakeLoan {
+    pub player: usize,
+    pub amount: i32,
+}
+
+#[derive(Event)]
+pub struct RepayLoan {
+    pub player: usize,
+    pub amount: i32,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<TakeLoan>()
+        .add_event::<RepayLoan>()
+        .add_systems(
+            Update,
+            (
+                handle_take_loan,
+                handle_repay_loan,
+                (charge_interest, handle_insolvency).chain(),
+            ),
+        );
+}
+
+fn handle_take_loan(mut er_take_loan: EventReader<TakeLoan>, mut state: ResMut<GameState>) {
+    for event in er_take_loan.read() {
+        state.take_loan(event.player, event.amount, INTEREST_RATE);
+    }
+}
+
+fn handle_repay_loan(mut er_repay_loan: EventReader<RepayLoan>, mut state: ResMut<GameState>) {
+    for event in er_repay_loan.read() {
+        state.repay_loan(event.player, event.amount);
+    }
+}
+
+fn charge_interest(mut er_turn_ended: EventReader<TurnEnded>, mut state: ResMut<GameState>) {
+    for event in er_turn_ended.read() {
+        state.charge_interest(event.player);
+    }
+}
+
+/// A player who ends their turn without funds first has their chips sold at a
+/// discount, then is offered an emergency loan for the rest of the shortfall.
+/// Only if the credit limit can't cover it are they declared bankrupt.
+fn handle_insolvency(
+    mut er_turn_ended: EventReader<TurnEnded>,
+    mut state: ResMut<GameState>,
+    chips: Query<(Entity, &Chip<ChipType>, &ChipArea)>,
+    mut ew_discard_chip: EventWriter<DiscardChip>,
+) {
+    for event in er_turn_ended.read() {
+        // Chips spoiling this turn are discarded by the warehouse, not sold.
+        let chips_of_player = chips
+            .iter()
+            .filter(|(_, chip, area)| {
+                area.player == event.player && !chip_expired(&state, chip, area)
+            })
+            .map(|(entity, chip, _)| (entity, chip.data))
+            .collect();
+
+        for entity in cover_shortfall(&mut state, event.player, chips_of_player) {
+            ew_discard_chip.send(DiscardChip { entity });
+        }
+    }
+}
+
+/// Brings `player` back above zero, or declares them bankrupt. Returns the
+/// chips sold off along the way.
+fn cover_shortfall(
+    state: &mut GameState,
+    player: usize,
+    mut chips: Vec<(Entity, ChipType)>,
+) -> Vec<Entity> {
+    let mut sold = vec![];
+    if state.get_balance(player) > 0 {
+        return sold;
+    }
+
+    // Sell the most valuable chips first so fewer of them are lost.
+    chips.sort_by_key(|(_, chip_type)| -chip_type.price());
+    for (entity, chip_type) in chips {
+        if state.get_balance(player) > 0 {
+            return sold;
+        }
+        sold.push(entity);
+        let proceeds = chip_type.price() * (100 - LIQUIDATION_DISCOUNT) / 100;
+        state.change_balance(player, proceeds, TransactionKind::Liquidation, None);
+    }
+
+    if state.get_balance(player) > 0 {
+        return sold;
+    }
+
+    let shortfall = -state.get_balance(player);
+    let amount = (shortfall + LOAN_AMOUNT).min(state.get_available_credit(player));
+    if amount > shortfall {
+        state.take_loan(player, amount, EMERGENCY_INTEREST_RATE);
+    } else {
+        state.declare_bankrupt(player);
+    }
+    sold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::cards::TurnPhase;
+
+    fn broke(state: &mut GameState, player: usize, balance: i32) {
+        let change = balance - state.get_balance(player);
+        state.change_balance(player, change, TransactionKind::Interest, None);
+    }
+
+    #[test]
+    fn loans_stop_at_the_credit_limit() {
+        let mut state = GameState::new(2);
+        let bank = state.get_balance(1);
+
+        assert!(state.take_loan(1, CREDIT_LIMIT - LOAN_AMOUNT, INTEREST_RATE));
+        assert!(!state.take_loan(1, 2 * LOAN_AMOUNT, INTEREST_RATE));
+        assert!(state.take_loan(1, LOAN_AMOUNT, INTEREST_RATE));
+        assert!(!state.take_loan(1, 0, INTEREST_RATE));
+
+        assert_eq!(state.get_balance(1), bank + CREDIT_LIMIT);
+        assert_eq!(state.get_available_credit(1), 0);
+        assert_eq!(state.get_available_credit(2), CREDIT_LIMIT);
+    }
+
+    #[test]
+    fn repayment_is_capped_by_debt_and_bank() {
+        let mut state = GameState::new(2);
+        state.take_loan(1, LOAN_AMOUNT, INTEREST_RATE);
+        state.take_loan(1, LOAN_AMOUNT, INTEREST_RATE);
+
+        assert_eq!(state.repay_loan(1, LOAN_AMOUNT / 2), LOAN_AMOUNT / 2);
+        assert_eq!(state.get_debt(1), 3 * LOAN_AMOUNT / 2);
+
+        assert_eq!(state.repay_loan(1, 10 * LOAN_AMOUNT), 3 * LOAN_AMOUNT / 2);
+        assert_eq!(state.get_debt(1), 0);
+
+        broke(&mut state, 1, 100);
+        state.take_loan(1, LOAN_AMOUNT, INTEREST_RATE);
+        broke(&mut state, 1, 100);
+        assert_eq!(state.repay_loan(1, LOAN_AMOUNT), 100);
+        assert_eq!(state.get_balance(1), 0);
+    }
+
+    #[test]
+    fn interest_is_charged_per_loan_and_rounded_up() {
+        let mut state = GameState::new(2);
+        let bank = state.get_balance(1);
+        state.take_loan(1, 1010, INTEREST_RATE);
+        state.take_loan(1, LOAN_AMOUNT, EMERGENCY_INTEREST_RATE);
+
+        // 5% of 1010 is 50.5, charged as 51.
+        let interest = 51 + LOAN_AMOUNT * EMERGENCY_INTEREST_RATE / 100;
+        assert_eq!(state.charge_interest(1), interest);
+        assert_eq!(state.get_balance(1), bank + 1010 + LOAN_AMOUNT - interest);
+        assert_eq!(state.charge_interest(2), 0);
+    }
+
+    #[test]
+    fn liquidation_sells_the_most_valuable_chips_first() {
+        let mut state = GameState::new(2);
+        broke(&mut state, 1, -100);
+        let chips = vec![
+            (Entity::from_raw(1), ChipType::CANNABIS),
+            (Entity::from_raw(2), ChipType::COCAINE),
+        ];
+
+        let sold = cover_shortfall(&mut state, 1, chips);
+
+        assert_eq!(sold, vec![Entity::from_raw(2)]);
+        let proceeds = ChipType::COCAINE.price() * (100 - LIQUIDATION_DISCOUNT) / 100;
+        assert_eq!(state.get_balance(1), proceeds - 100);
+        assert_eq!(state.get_debt(1), 0);
+    }
+
+    #[test]
+    fn liquidation_falls_back_to_an_emergency_loan() {
+        let mut state = GameState::new(2);
+        broke(&mut state, 1, -LOAN_AMOUNT);
+
+        assert!(cover_shortfall(&mut state, 1, vec![]).is_empty());
+
+        assert_eq!(state.get_debt(1), 2 * LOAN_AMOUNT);
+        assert_eq!(state.get_balance(1), LOAN_AMOUNT);
+        assert!(!state.is_bankrupt(1));
+    }
+
+    #[test]
+    fn a_shortfall_past_the_credit_limit_is_bankruptcy() {
+        let mut state = GameState::new(2);
+        broke(&mut state, 1, -CREDIT_LIMIT);
+
+        cover_shortfall(&mut state, 1, vec![]);
+
+        assert!(state.is_bankrupt(1));
+        assert_eq!(state.get_debt(1), 0);
+    }
+
+    #[test]
+    fn bankrupt_players_are_skipped() {
+        let mut state = GameState::new(3);
+        state.declare_bankrupt(2);
+        state.phase = TurnPhase::End;
+
+        state.advance();
+
+        assert_eq!(state.player, 3);
+        assert_eq!(state.phase, TurnPhase::Prepare);
+    }
+}
@@ -3,11 +3,30 @@
 use bevy::prelude::*;
 
 // mod animation;
+pub mod ai;
 pub mod assets;
 pub mod audio;
+pub mod camera;
+pub mod campaign;
 pub mod cards;
+pub mod decks;
+pub mod draft;
+pub mod effects;
+pub mod input;
+pub mod loans;
+pub mod objectives;
+pub mod placement;
+pub mod preview;
+pub mod products;
+pub mod research;
+pub mod settings;
 pub mod spawn;
+pub mod territory;
+pub mod trade;
+pub mod transport;
+pub mod tutorial;
 pub mod ui;
+pub mod warehouse;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
@@ -16,6 +35,25 @@ pub(super) fn plugin(app: &mut App) {
         assets::plugin,
         spawn::plugin,
         cards::plugin,
-        ui::plugin,
+        ai::plugin,
+        camera::plugin,
+        campaign::plugin,
+        decks::plugin,
+        loans::plugin,
+        objectives::plugin,
+        transport::plugin,
+        research::plugin,
+        // Tuples of plugins are limited to 15, so the rest are grouped.
+        (
+            input::plugin,
+            placement::plugin,
+            preview::plugin,
+            settings::plugin,
+            territory::plugin,
+            trade::plugin,
+            tutorial::plugin,
+            ui::plugin,
+            warehouse::plugin,
+        ),
     ));
 }
@@ -0,0 +1,216 @@
+//! Scenario objectives. Each objective is a goal for player 1 tracked from
+//! the rules' outcome events; finishing one ends the match as a win or a
+//! loss, on top of the usual end conditions.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    campaign::ScenarioCard,
+    cards::{ChipSold, GameOver, GameState, TurnEnded},
+    transport::SeizeChip,
+};
+
+/// Objectives are always about the human, who plays first.
+pub const OBJECTIVE_PLAYER: usize = 1;
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum Goal {
+    /// Sell chips, optionally of one product or through one sales card.
+    SellChips {
+        product: Option<String>,
+        via: Option<ScenarioCard>,
+        count: usize,
+    },
+    /// End turns in a row with at least `amount` in the bank.
+    KeepBankAbove { amount: i32, turns: usize },
+    /// Have opponents' shipments seized.
+    DestroyEnemyChips { count: usize },
+    /// End a turn with less than `amount` in the bank.
+    BankBelow { amount: i32 },
+}
+
+impl Goal {
+    fn target(&self) -> usize {
+        match self {
+            Goal::SellChips { count, .. } | Goal::DestroyEnemyChips { count } => *count,
+            Goal::KeepBankAbove { turns, .. } => *turns,
+            Goal::BankBelow { .. } => 1,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Goal::SellChips {
+                product,
+                via,
+                count,
+            } => {
+                let product = product.as_deref().unwrap_or("any");
+                let via = match via.as_ref().and_then(ScenarioCard::card_type) {
+                    Some(card_type) => format!(" via {}", card_type.name()),
+                    None => String::new(),
+                };
+                format!("Sell {} {} chips{}", count, product, via)
+            }
+            Goal::KeepBankAbove { amount, turns } => {
+                format!("End {} turns in a row with ${}+", turns, amount)
+            }
+            Goal::DestroyEnemyChips { count } => format!("Have {} enemy chips seized", count),
+            Goal::BankBelow { amount } => format!("Don't end a turn below ${}", amount),
+        }
+    }
+}
+
+/// What finishing the objective does to the match.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum Outcome {
+    Win,
+    Lose,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Objective {
+    pub goal: Goal,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug)]
+pub struct ObjectiveProgress {
+    pub objective: Objective,
+    pub progress: usize,
+}
+
+impl ObjectiveProgress {
+    pub fn is_done(&self) -> bool {
+        self.progress >= self.objective.goal.target()
+    }
+
+    pub fn describe(&self) -> String {
+        let status = match self.objective.outcome {
+            Outcome::Win => format!("{}/{}", self.progress, self.objective.goal.target()),
+            Outcome::Lose => "fail".to_string(),
+        };
+        format!("{} ({})", self.objective.goal.describe(), status)
+    }
+}
+
+/// Objectives of the current match. Empty outside scenarios.
+#[derive(Resource, Default)]
+pub struct Objectives {
+    pub objectives: Vec<ObjectiveProgress>,
+    /// Set once an objective has ended the match.
+    pub finished: bool,
+}
+
+impl Objectives {
+    pub fn new(objectives: &[Objective]) -> Self {
+        Self {
+            objectives: objectives
+                .iter()
+                .map(|objective| ObjectiveProgress {
+                    objective: objective.clone(),
+                    progress: 0,
+                })
+                .collect(),
+            finished: false,
+        }
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Objectives>().add_systems(
+        Update,
+        (track_sales, track_seizures, track_bank, check_objectives).chain(),
+    );
+}
+
+fn track_sales(mut er_chip_sold: EventReader<ChipSold>, mut objectives: ResMut<Objectives>) {
+    for event in er_chip_sold.read() {
+        if event.player != OBJECTIVE_PLAYER {
+            continue;
+        }
+        for tracker in objectives.objectives.iter_mut() {
+            let Goal::SellChips { product, via, .. } = &tracker.objective.goal else {
+                continue;
+            };
+            let product_matches = product
+                .as_ref()
+                .map_or(true, |name| event.chip_type.product().name == name.as_str());
+            let via_matches = via
+                .as_ref()
+                .map_or(true, |card| card.card_type() == Some(event.card_type));
+            if product_matches && via_matches {
+                tracker.progress += 1;
+            }
+        }
+    }
+}
+
+fn track_seizures(mut er_seize_chip: EventReader<SeizeChip>, mut objectives: ResMut<Objectives>) {
+    for event in er_seize_chip.read() {
+        if event.player == OBJECTIVE_PLAYER {
+            continue;
+        }
+        for tracker in objectives.objectives.iter_mut() {
+            if let Goal::DestroyEnemyChips { .. } = tracker.objective.goal {
+                tracker.progress += 1;
+            }
+        }
+    }
+}
+
+fn track_bank(
+    mut er_turn_ended: EventReader<TurnEnded>,
+    mut objectives: ResMut<Objectives>,
+    state: Res<GameState>,
+) {
+    for event in er_turn_ended.read() {
+        if event.player != OBJECTIVE_PLAYER {
+            continue;
+        }
+        let balance = state.get_balance(OBJECTIVE_PLAYER);
+        for tracker in objectives.objectives.iter_mut() {
+            match tracker.objective.goal {
+                Goal::KeepBankAbove { amount, .. } => {
+                    tracker.progress = if balance >= amount {
+                        tracker.progress + 1
+                    } else {
+                        0
+                    };
+                }
+                Goal::BankBelow { amount } if balance < amount => {
+                    tracker.progress = 1;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn check_objectives(
+    mut objectives: ResMut<Objectives>,
+    mut state: ResMut<GameState>,
+    mut ew_game_over: EventWriter<GameOver>,
+) {
+    if objectives.finished {
+        return;
+    }
+    let Some(outcome) = objectives
+        .objectives
+        .iter()
+        .find(|tracker| tracker.is_done())
+        .map(|tracker| tracker.objective.outcome)
+    else {
+        return;
+    };
+
+    objectives.finished = true;
+    let player_won = match outcome {
+        Outcome::Win => OBJECTIVE_PLAYER,
+        Outcome::Lose => OBJECTIVE_PLAYER % state.num_players() + 1,
+    };
+    if let Some(event) = state.finish(player_won) {
+        ew_game_over.send(event);
+    }
+}
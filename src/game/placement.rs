@@ -0,0 +1,239 @@
+//! Drag-and-drop onto the play slots. Pressing a card picks it up; it follows
+//! the cursor while the button is held and snaps to the slot under it when
+//! dropped. Cards already on the table can be moved to another slot, or swap
+//! with the card there. A click without dragging plays a hand card into the
+//! leftmost free slot.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_la_mesa::{
+    events::{AlignCardsInHand, PlaceCardOnTable},
+    Card, CardOnTable, Hand, PlayArea,
+};
+
+use super::{
+    cards::{CardRejected, GameState, Kard, TransactionKind, PLAY_SLOTS},
+    spawn::level::SpawnBoard,
+};
+use crate::{screen::Screen, GameCamera};
+
+/// How close to a slot, in table units, a dropped card snaps to it.
+const SNAP_DISTANCE: f32 = 1.8;
+/// Cursor travel, in pixels, that turns a click into a drag.
+const DRAG_THRESHOLD: f32 = 8.0;
+/// Height a dragged card floats above the table.
+const DRAG_HEIGHT: f32 = 1.0;
+
+pub struct Drag {
+    pub card_entity: Entity,
+    pub player: usize,
+    /// Slot the card sat in, if it was already on the table.
+    pub from_slot: Option<usize>,
+    pub cursor_start: Vec2,
+    pub moved: bool,
+    /// Slot the card would drop into.
+    pub snap: Option<usize>,
+}
+
+/// Card being dragged, if any.
+#[derive(Resource, Default)]
+pub struct DraggedCard(pub Option<Drag>);
+
+#[derive(Component)]
+struct SlotHighlight;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DraggedCard>()
+        .observe(spawn_slot_highlight)
+        .add_systems(
+            Update,
+            (drag_card, drop_card)
+                .chain()
+                .run_if(in_state(Screen::Playing)),
+        );
+}
+
+fn spawn_slot_highlight(
+    _trigger: Trigger<SpawnBoard>,
+    mut commands: Commands,
+    mut dragged_card: ResMut<DraggedCard>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    dragged_card.0 = None;
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Plane3d::default().mesh().size(2.7, 3.7)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgba(0.867, 0.827, 0.412, 0.4),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        SlotHighlight,
+        StateScoped(Screen::Playing),
+        Name::new("Slot Highlight"),
+    ));
+}
+
+/// Where the cursor points on the table, and where it is on screen.
+fn cursor_on_table(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<(Vec2, Vec3)> {
+    let cursor = window.cursor_position()?;
+    let ray = camera.viewport_to_world(camera_transform, cursor)?;
+    let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y))?;
+    Some((cursor, ray.get_point(distance)))
+}
+
+fn drag_card(
+    mut dragged_card: ResMut<DraggedCard>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    slots: Query<(&PlayArea, &GlobalTransform)>,
+    mut cards: Query<&mut Transform, With<Card<Kard>>>,
+    mut highlight: Query<
+        (&mut Transform, &mut Visibility),
+        (With<SlotHighlight>, Without<Card<Kard>>),
+    >,
+) {
+    let Some(drag) = dragged_card.0.as_mut() else {
+        return;
+    };
+    if !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (window.get_single(), camera.get_single())
+    else {
+        return;
+    };
+    let Some((cursor, point)) = cursor_on_table(window, camera, camera_transform) else {
+        return;
+    };
+
+    drag.moved |= cursor.distance(drag.cursor_start) > DRAG_THRESHOLD;
+    if !drag.moved {
+        return;
+    }
+    if let Ok(mut transform) = cards.get_mut(drag.card_entity) {
+        transform.translation = point + Vec3::Y * DRAG_HEIGHT;
+    }
+
+    let snap = slots
+        .iter()
+        .filter(|(slot, _)| slot.player == drag.player && slot.marker <= PLAY_SLOTS)
+        .map(|(slot, transform)| {
+            let offset = transform.translation() - point;
+            (
+                slot.marker,
+                transform,
+                Vec2::new(offset.x, offset.z).length(),
+            )
+        })
+        .filter(|(_, _, distance)| *distance < SNAP_DISTANCE)
+        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+    drag.snap = snap.map(|(marker, _, _)| marker);
+
+    let Ok((mut highlight_transform, mut visibility)) = highlight.get_single_mut() else {
+        return;
+    };
+    match snap {
+        Some((_, slot_transform, _)) => {
+            *highlight_transform = slot_transform.compute_transform();
+            highlight_transform.translation.y += 0.01;
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+fn drop_card(
+    mut dragged_card: ResMut<DraggedCard>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut state: ResMut<GameState>,
+    cards_in_hand: Query<&Card<Kard>, With<Hand>>,
+    cards_on_table: Query<(Entity, &CardOnTable)>,
+    mut highlight: Query<&mut Visibility, With<SlotHighlight>>,
+    mut ew_place_card_on_table: EventWriter<PlaceCardOnTable>,
+    mut ew_align_cards_in_hand: EventWriter<AlignCardsInHand>,
+    mut ew_card_rejected: EventWriter<CardRejected>,
+) {
+    if dragged_card.0.is_none() || mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(drag) = dragged_card.0.take() else {
+        return;
+    };
+    for mut visibility in &mut highlight {
+        *visibility = Visibility::Hidden;
+    }
+
+    let player = drag.player;
+    let mut place = |card_entity: Entity, marker: usize| {
+        ew_place_card_on_table.send(PlaceCardOnTable {
+            card_entity,
+            marker,
+            player,
+        });
+    };
+
+    // Moving a card that is already in play.
+    if let Some(from_slot) = drag.from_slot {
+        let to_slot = drag
+            .snap
+            .filter(|to_slot| *to_slot != from_slot && state.check_table_change(player).is_ok());
+        match to_slot {
+            Some(to_slot) => {
+                let occupant = cards_on_table
+                    .iter()
+                    .find(|(_, on_table)| on_table.player == player && on_table.marker == to_slot);
+                if let Some((occupant, _)) = occupant {
+                    place(occupant, from_slot);
+                }
+                place(drag.card_entity, to_slot);
+            }
+            None if drag.moved => place(drag.card_entity, from_slot),
+            None => {}
+        }
+        return;
+    }
+
+    // Playing a card from hand.
+    let Ok(card) = cards_in_hand.get(drag.card_entity) else {
+        return;
+    };
+    if drag.moved && drag.snap.is_none() {
+        ew_align_cards_in_hand.send(AlignCardsInHand { player });
+        return;
+    }
+
+    let occupied: Vec<usize> = cards_on_table
+        .iter()
+        .filter(|(_, on_table)| on_table.player == player)
+        .map(|(_, on_table)| on_table.marker)
+        .collect();
+    match state.check_card_play(player, &card.data, &occupied, drag.snap) {
+        Ok(play) => {
+            state.draw_bank(
+                player,
+                play.price,
+                TransactionKind::CardPurchase,
+                Some(card.data.card_type),
+            );
+            place(drag.card_entity, play.marker);
+        }
+        Err(blocker) => {
+            ew_card_rejected.send(CardRejected {
+                card_entity: drag.card_entity,
+                blocker,
+            });
+            ew_align_cards_in_hand.send(AlignCardsInHand { player });
+        }
+    }
+}
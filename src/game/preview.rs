@@ -0,0 +1,294 @@
+//! Enlarged preview of the card under the cursor, with what it costs and
+//! what it would do right now.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_la_mesa::{
+    events::{CardHover, CardOut},
+    Card, CardOnTable, Hand,
+};
+
+use super::{
+    cards::{CardType, ChipType, GameState, Kard, PRODUCTION_PER_CARD},
+    effects::{EffectDuration, EffectType, Stat, TriggerAction},
+    spawn::level::SpawnBoard,
+    territory::Region,
+    transport::interception_chance,
+    warehouse::WAREHOUSE_UPGRADE,
+};
+use crate::{screen::Screen, ui::palette::*};
+
+/// Distance between the cursor and the preview.
+const PREVIEW_OFFSET: f32 = 24.0;
+
+/// Card the cursor is over, if any.
+#[derive(Resource, Default)]
+pub struct HoveredCard(pub Option<Entity>);
+
+#[derive(Component, Default)]
+struct CardPreview {
+    shown: Option<Entity>,
+}
+
+#[derive(Component)]
+struct CardPreviewImage;
+
+#[derive(Component, Clone, Copy)]
+enum CardPreviewText {
+    Name,
+    Cost,
+    Rules,
+    Effect,
+    Flavour,
+    Status,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<HoveredCard>()
+        .observe(spawn_card_preview)
+        .add_systems(
+            Update,
+            (track_hovered_card, update_card_preview)
+                .chain()
+                .run_if(in_state(Screen::Playing)),
+        );
+}
+
+fn spawn_card_preview(
+    _trigger: Trigger<SpawnBoard>,
+    mut commands: Commands,
+    mut hovered_card: ResMut<HoveredCard>,
+) {
+    hovered_card.0 = None;
+
+    commands
+        .spawn((
+            Name::new("Card Preview"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(260.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor(HUD_PANEL_BACKGROUND),
+                border_radius: BorderRadius::all(Val::Px(6.0)),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            CardPreview::default(),
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            children.spawn((
+                Name::new("Card Preview Image"),
+                ImageBundle {
+                    style: Style {
+                        width: Val::Px(180.0),
+                        height: Val::Px(252.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                CardPreviewImage,
+            ));
+            for (text, font_size, color) in [
+                (CardPreviewText::Name, 24.0, HEADER_TEXT),
+                (CardPreviewText::Cost, 16.0, Color::WHITE),
+                (CardPreviewText::Rules, 14.0, Color::WHITE),
+                (CardPreviewText::Effect, 14.0, LABEL_TEXT),
+                (CardPreviewText::Flavour, 12.0, Color::srgb(0.6, 0.6, 0.6)),
+                (CardPreviewText::Status, 16.0, Color::WHITE),
+            ] {
+                children.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size,
+                            color,
+                            ..default()
+                        },
+                    )
+                    .with_style(Style {
+                        max_width: Val::Px(240.0),
+                        ..default()
+                    }),
+                    text,
+                ));
+            }
+        });
+}
+
+fn track_hovered_card(
+    mut er_card_hover: EventReader<CardHover>,
+    mut er_card_out: EventReader<CardOut>,
+    mut hovered_card: ResMut<HoveredCard>,
+) {
+    for event in er_card_out.read() {
+        if hovered_card.0 == Some(event.card_entity) {
+            hovered_card.0 = None;
+        }
+    }
+    for event in er_card_hover.read() {
+        hovered_card.0 = Some(event.card_entity);
+    }
+}
+
+fn update_card_preview(
+    hovered_card: Res<HoveredCard>,
+    state: Res<GameState>,
+    asset_server: Res<AssetServer>,
+    cards: Query<(&Card<Kard>, Option<&Hand>, Option<&CardOnTable>)>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut previews: Query<(&mut CardPreview, &mut Style, &mut Visibility)>,
+    mut images: Query<&mut UiImage, With<CardPreviewImage>>,
+    mut texts: Query<(&CardPreviewText, &mut Text)>,
+) {
+    let Ok((mut preview, mut style, mut visibility)) = previews.get_single_mut() else {
+        return;
+    };
+
+    // Only cards in a hand or on the table; the decks stay face down.
+    let hovered = hovered_card.0.and_then(|entity| {
+        let (card, hand, on_table) = cards.get(entity).ok()?;
+        let owner = hand
+            .map(|hand| hand.player)
+            .or(on_table.map(|on_table| on_table.player))?;
+        Some((entity, &card.data, owner, hand.is_some()))
+    });
+    let Some((entity, kard, owner, in_hand)) = hovered else {
+        *visibility = Visibility::Hidden;
+        preview.shown = None;
+        return;
+    };
+    *visibility = Visibility::Visible;
+
+    if let Some(cursor) = window.get_single().ok().and_then(Window::cursor_position) {
+        style.left = Val::Px(cursor.x + PREVIEW_OFFSET);
+        style.top = Val::Px((cursor.y - 300.0).max(0.0));
+    }
+
+    if preview.shown != Some(entity) {
+        preview.shown = Some(entity);
+        for mut image in &mut images {
+            image.texture = asset_server.load(&kard.filename);
+        }
+    }
+
+    let price = state.modify_stat(owner, Stat::CardCost, kard.price);
+    let status = if !in_hand {
+        format!("In play for player {}", owner)
+    } else if owner != state.player {
+        format!("In player {}'s hand", owner)
+    } else {
+        let occupied: Vec<usize> = cards
+            .iter()
+            .filter_map(|(_, _, on_table)| on_table)
+            .filter(|on_table| on_table.player == owner)
+            .map(|on_table| on_table.marker)
+            .collect();
+        match state.check_card_play(owner, kard, &occupied, None) {
+            Ok(_) => "Playable".to_string(),
+            Err(blocker) => blocker.message(),
+        }
+    };
+
+    for (text, mut value) in &mut texts {
+        value.sections[0].value = match text {
+            CardPreviewText::Name => kard.card_type.name(),
+            CardPreviewText::Cost if price != kard.price => {
+                format!("Cost: ${} (base ${})", price, kard.price)
+            }
+            CardPreviewText::Cost => format!("Cost: ${}", price),
+            CardPreviewText::Rules => kard.card_type.rules().to_string(),
+            CardPreviewText::Effect => describe_effect(&state, owner, kard.card_type),
+            CardPreviewText::Flavour => kard.flavour.clone(),
+            CardPreviewText::Status => status.clone(),
+        };
+    }
+}
+
+/// What the card would do for `player` right now, with every modifier
+/// applied.
+fn describe_effect(state: &GameState, player: usize, card_type: CardType) -> String {
+    match card_type {
+        CardType::Production(chip_type) => format!(
+            "Drops {} {} chips.",
+            state
+                .modify_stat(player, Stat::Production, PRODUCTION_PER_CARD)
+                .max(0),
+            chip_type.product().name
+        ),
+        CardType::Truck | CardType::Train => {
+            let capacity = state.modify_stat(player, Stat::TransportCapacity, card_type.capacity());
+            let empty = interception_chance(state, player, card_type, 0);
+            let per_chip = interception_chance(state, player, card_type, 1) - empty;
+            format!(
+                "Carries weight {}. Interception risk {:.0}% plus {:.0}% per chip.",
+                capacity.max(0),
+                empty * 100.0,
+                per_chip * 100.0
+            )
+        }
+        CardType::Export | CardType::LocalMarket => {
+            let prices: Vec<String> = ChipType::all()
+                .map(|chip_type| {
+                    let mut price = state.modify_stat(player, Stat::SalePrice, chip_type.price());
+                    if card_type == CardType::LocalMarket {
+                        price = state.modify_stat(player, Stat::LocalMarketPrice, price);
+                    }
+                    format!("{} ${}", chip_type.product().name, price)
+                })
+                .collect();
+            format!(
+                "Moves weight {}. Pays per chip: {}.",
+                card_type.capacity(),
+                prices.join(", ")
+            )
+        }
+        CardType::Warehouse => format!(
+            "Production room {} -> {}, transport room {} -> {}.",
+            state.get_storage_capacity(player, 1),
+            state.get_storage_capacity(player, 1) + WAREHOUSE_UPGRADE,
+            state.get_storage_capacity(player, 2),
+            state.get_storage_capacity(player, 2) + WAREHOUSE_UPGRADE
+        ),
+        CardType::Influence => {
+            let controlled = Region::ALL
+                .into_iter()
+                .filter(|region| state.region_controller(*region) == Some(player))
+                .count();
+            format!(
+                "One more marker. You control {} of {} regions.",
+                controlled,
+                Region::ALL.len()
+            )
+        }
+        CardType::Bribe => describe_effect_type(EffectType::Bribe),
+        CardType::Attack => describe_effect_type(EffectType::Attack),
+        CardType::Drought => describe_effect_type(EffectType::Drought),
+        CardType::BigDeal => describe_effect_type(EffectType::BigDeal),
+    }
+}
+
+fn describe_effect_type(effect_type: EffectType) -> String {
+    let definition = effect_type.definition();
+    let mut parts: Vec<String> = definition
+        .modifiers
+        .iter()
+        .map(|modifier| modifier.describe())
+        .collect();
+    for (_, action) in definition.hooks {
+        if let TriggerAction::Charge(amount) = action {
+            parts.push(format!("${} fine every turn", amount));
+        }
+    }
+    let duration = match definition.duration {
+        EffectDuration::PlayerTurns(turns) => format!("{} turns", turns),
+        EffectDuration::Rounds(rounds) => format!("{} round(s)", rounds),
+    };
+    format!("{} for {}.", parts.join(", "), duration)
+}
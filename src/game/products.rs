@@ -0,0 +1,38 @@
+//! Product definitions, loaded from `assets/products.ron`. Every chip on the
+//! table is one of these, and [`ChipType`](super::cards::ChipType) is an
+//! index into [`PRODUCTS`].
+
+use std::sync::LazyLock;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+pub struct Product {
+    pub name: String,
+    /// OBJ scene for the chip. Products without one are drawn as a plain
+    /// disc in `color`.
+    pub model: Option<String>,
+    #[serde(deserialize_with = "srgb")]
+    pub color: Color,
+    /// Where the product's stack sits on a player's resource board, as an
+    /// `(x, z)` offset from the board's corner.
+    pub stack_position: (f32, f32),
+    /// Payout per chip sold.
+    pub price: i32,
+    /// Transport and sales capacity a single chip takes up.
+    pub weight: i32,
+    pub card_price: i32,
+    pub card_filename: String,
+    /// Production cards of this product in the deck, per player.
+    pub cards_per_player: usize,
+}
+
+pub static PRODUCTS: LazyLock<Vec<Product>> = LazyLock::new(|| {
+    ron::from_str(include_str!("../../assets/products.ron")).expect("products should be valid RON")
+});
+
+fn srgb<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let (red, green, blue) = <(f32, f32, f32)>::deserialize(deserializer)?;
+    Ok(Color::srgb(red, green, blue))
+}
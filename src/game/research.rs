@@ -0,0 +1,114 @@
+//! Per-player tech tree. Researched technologies are permanent modifiers
+//! that [`GameState::modify_stat`] applies alongside effects.
+
+use bevy::prelude::*;
+
+use super::{
+    cards::{GameState, TransactionKind, TurnPhase},
+    effects::{Modifier, Stat},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum Technology {
+    RefinedLogistics,
+    LabEquipment,
+    OffshoreAccounts,
+}
+
+pub struct TechnologyDefinition {
+    pub name: &'static str,
+    pub cost: i32,
+    pub requires: Option<Technology>,
+    pub modifiers: &'static [Modifier],
+    pub color: Color,
+}
+
+impl Technology {
+    pub const ALL: [Technology; 3] = [
+        Technology::RefinedLogistics,
+        Technology::LabEquipment,
+        Technology::OffshoreAccounts,
+    ];
+
+    pub fn definition(&self) -> &'static TechnologyDefinition {
+        match self {
+            Technology::RefinedLogistics => &TechnologyDefinition {
+                name: "Refined Logistics",
+                cost: 2000,
+                requires: None,
+                modifiers: &[Modifier::Add(Stat::TransportCapacity, 6)],
+                color: Color::srgb(0.9, 0.6, 0.1),
+            },
+            Technology::LabEquipment => &TechnologyDefinition {
+                name: "Lab Equipment",
+                cost: 2500,
+                requires: None,
+                modifiers: &[Modifier::Add(Stat::Production, 2)],
+                color: Color::srgb(0.2, 0.7, 0.9),
+            },
+            Technology::OffshoreAccounts => &TechnologyDefinition {
+                name: "Offshore Accounts",
+                cost: 3000,
+                requires: Some(Technology::RefinedLogistics),
+                modifiers: &[
+                    Modifier::Percent(Stat::InterceptionRisk, -30),
+                    Modifier::Add(Stat::SeizureTurns, 3),
+                ],
+                color: Color::srgb(0.8, 0.75, 0.2),
+            },
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct Research {
+    pub player: usize,
+    pub technology: Technology,
+}
+
+/// Sent once a technology has been paid for, so the board can show it.
+#[derive(Event)]
+pub struct TechnologyResearched {
+    pub player: usize,
+    pub technology: Technology,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<Research>()
+        .add_event::<TechnologyResearched>()
+        .add_systems(Update, handle_research);
+}
+
+fn handle_research(
+    mut er_research: EventReader<Research>,
+    mut ew_researched: EventWriter<TechnologyResearched>,
+    mut state: ResMut<GameState>,
+) {
+    for event in er_research.read() {
+        // Only on the researcher's own turn, before the cards resolve.
+        if event.player != state.player
+            || !matches!(
+                state.phase,
+                TurnPhase::Prepare | TurnPhase::PlaceCardsOnTable
+            )
+            || state.is_bankrupt(event.player)
+            || !state.can_research(event.player, event.technology)
+        {
+            continue;
+        }
+
+        let definition = event.technology.definition();
+        state.draw_bank(
+            event.player,
+            definition.cost,
+            TransactionKind::Research,
+            None,
+        );
+        state.research[event.player - 1].push(event.technology);
+
+        ew_researched.send(TechnologyResearched {
+            player: event.player,
+            technology: event.technology,
+        });
+    }
+}
@@ -0,0 +1,195 @@
+//! Player options: volumes, shadows, animation speed and the colour-blind
+//! palette. They are saved together with the input bindings, to the user's
+//! config directory on native and to local storage on the web.
+
+use std::time::Duration;
+
+use bevy::{audio::Volume, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cards::{PhaseTimer, PHASE_DELAY_SECONDS},
+    input::{InputBindings, SavedBinding},
+};
+
+#[cfg(not(target_family = "wasm"))]
+const SETTINGS_STORAGE: Storage = Storage::Config("settings.ron");
+#[cfg(target_family = "wasm")]
+const SETTINGS_STORAGE: Storage = Storage::Config("bevy_cartel_settings");
+
+/// Folder of the game's files in the user's directories.
+#[cfg(not(target_family = "wasm"))]
+const APP_DIRECTORY: &str = "bevy_cartel";
+
+/// Global volume at full master volume.
+pub const BASE_VOLUME: f32 = 0.3;
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub shadows: bool,
+    /// How fast animations play; 2.0 is twice as fast.
+    pub animation_speed: f32,
+    pub colour_blind: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            shadows: true,
+            animation_speed: 1.0,
+            colour_blind: false,
+        }
+    }
+}
+
+impl Settings {
+    /// How long an animation of `duration` lasts at the chosen speed.
+    pub fn animation(&self, duration: Duration) -> Duration {
+        duration.div_f32(self.animation_speed.max(0.1))
+    }
+
+    pub fn global_volume(&self) -> f32 {
+        BASE_VOLUME * self.master_volume
+    }
+
+    /// Writes these settings and `bindings` to storage.
+    pub fn save(&self, bindings: &InputBindings) {
+        SavedSettings {
+            settings: self.clone(),
+            bindings: bindings.to_saved(),
+        }
+        .save();
+    }
+}
+
+/// Everything written to storage.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedSettings {
+    settings: Settings,
+    bindings: Vec<SavedBinding>,
+}
+
+impl SavedSettings {
+    fn load() -> Self {
+        read_storage(SETTINGS_STORAGE)
+            .and_then(|contents| ron::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        match ron::to_string(self) {
+            Ok(contents) => {
+                if let Err(error) = write_storage(SETTINGS_STORAGE, &contents) {
+                    warn!("Couldn't save settings: {}", error);
+                }
+            }
+            Err(error) => warn!("Couldn't save settings: {}", error),
+        }
+    }
+}
+
+/// Somewhere to save to: a file name on native, a local storage key on the
+/// web.
+#[derive(Clone, Copy)]
+pub(super) enum Storage {
+    /// Player preferences, kept in the user's config directory.
+    Config(&'static str),
+    /// Game progress, kept in the user's data directory.
+    Data(&'static str),
+}
+
+impl Storage {
+    fn name(self) -> &'static str {
+        match self {
+            Storage::Config(name) | Storage::Data(name) => name,
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn path(self) -> Option<std::path::PathBuf> {
+        let directory = match self {
+            Storage::Config(_) => dirs::config_dir()?,
+            Storage::Data(_) => dirs::data_dir()?,
+        };
+        Some(directory.join(APP_DIRECTORY).join(self.name()))
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub(super) fn read_storage(storage: Storage) -> Option<String> {
+    std::fs::read_to_string(storage.path()?).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub(super) fn write_storage(storage: Storage, contents: &str) -> Result<(), String> {
+    let path = storage.path().ok_or("no user directory to save to")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    std::fs::write(path, contents).map_err(|error| error.to_string())
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) fn read_storage(storage: Storage) -> Option<String> {
+    local_storage()?.get_item(storage.name()).ok()?
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) fn write_storage(storage: Storage, contents: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("local storage is unavailable")?
+        .set_item(storage.name(), contents)
+        .map_err(|error| format!("{:?}", error))
+}
+
+pub(super) fn plugin(app: &mut App) {
+    let saved = SavedSettings::load();
+    app.insert_resource(saved.settings)
+        .insert_resource(InputBindings::from_saved(&saved.bindings))
+        .add_systems(
+            Update,
+            (
+                save_settings.run_if(settings_edited),
+                (apply_volume, apply_shadows, apply_animation_speed)
+                    .run_if(resource_changed::<Settings>),
+            ),
+        );
+}
+
+/// Changed by the player, as opposed to loaded at startup.
+fn settings_edited(settings: Res<Settings>, bindings: Res<InputBindings>) -> bool {
+    (settings.is_changed() && !settings.is_added())
+        || (bindings.is_changed() && !bindings.is_added())
+}
+
+fn save_settings(settings: Res<Settings>, bindings: Res<InputBindings>) {
+    settings.save(&bindings);
+}
+
+fn apply_volume(settings: Res<Settings>, mut global_volume: ResMut<GlobalVolume>) {
+    global_volume.volume = Volume::new(settings.global_volume());
+}
+
+fn apply_shadows(settings: Res<Settings>, mut lights: Query<&mut PointLight>) {
+    for mut light in &mut lights {
+        light.shadows_enabled = settings.shadows;
+    }
+}
+
+fn apply_animation_speed(settings: Res<Settings>, mut phase_timer: ResMut<PhaseTimer>) {
+    let duration = settings.animation(Duration::from_secs_f32(PHASE_DELAY_SECONDS));
+    phase_timer.0.set_duration(duration);
+}
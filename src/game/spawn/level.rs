@@ -4,15 +4,41 @@ use bevy::prelude::*;
 use bevy_la_mesa::{
     events::RenderDeck, Chip, ChipArea, DeckArea, HandArea, LaMesaPluginSettings, PlayArea,
 };
-use bevy_tweening::{lens::TransformPositionLens, Animator, EaseFunction, Tween};
+use bevy_tweening::{
+    lens::TransformPositionLens, Animator, EaseFunction, EaseMethod, Sequence, Tween,
+    TweenCompleted,
+};
 
 use crate::game::{
-    assets::{ChipModel, HandleMap},
+    assets::HandleMap,
+    campaign::{ActiveScenario, Campaign},
     cards::{
-        load_event_deck, load_playing_deck, ChipType, DiscardChip, DropChip, GameState, Kard,
-        MoveChip,
+        load_event_deck, load_starting_deck, ChipSold, ChipType, DiscardChip, DropChip, GameState,
+        Kard, MoveChip,
     },
+    draft::DraftedDecks,
+    products::PRODUCTS,
+    research::{Technology, TechnologyResearched},
+    settings::Settings,
+    territory::{load_influence_cards, InfluencePlaced, Region, TerritorySettings},
+    trade::TransferChip,
+    transport::SeizeChip,
+    tutorial::{load_tutorial_deck, Tutorial},
 };
+use crate::screen::Screen;
+
+/// `TweenCompleted::user_data` of chips flying off the table after a seizure.
+const SEIZED_CHIP_TWEEN: u64 = 1;
+/// `TweenCompleted::user_data` of sold chips flying to the seller's gem.
+const SOLD_CHIP_TWEEN: u64 = 2;
+/// `TweenCompleted::user_data` of chips lifted off the table when discarded.
+const DISCARDED_CHIP_TWEEN: u64 = 3;
+/// Time the racing gem takes to reach a new balance.
+const RACING_GEM_MILLIS: u64 = 600;
+/// Longest stretch of the lap the gem covers in a straight line.
+const RACING_GEM_STEP: f32 = std::f32::consts::PI / 12.0;
+/// Centre of the territory map, past the resource boards.
+const TERRITORY_MAP_POSITION: Vec3 = Vec3::new(11.0, 0.0, 0.0);
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_level);
@@ -22,8 +48,15 @@ pub(super) fn plugin(app: &mut App) {
         (
             handle_drop_chip,
             handle_move_chip_to_sales,
+            handle_transfer_chip,
             discard_chip,
+            seize_chip,
+            sell_chip,
+            despawn_tweened_chips,
             update_race_gem_positions,
+            spawn_technology_tokens,
+            spawn_influence_markers,
+            repaint_player_colors.run_if(resource_changed::<Settings>),
         ),
     )
     .add_systems(Startup, render_hand_area);
@@ -43,8 +76,25 @@ pub struct RacingCycle;
 #[derive(Component)]
 pub struct RacingGem {
     pub player: usize,
+    /// Balance the gem last moved to.
+    pub balance: i32,
 }
 
+/// Colour of a player's gem, influence markers and HUD panel.
+pub fn player_color(player: usize, colour_blind: bool) -> Color {
+    match (player, colour_blind) {
+        (1, false) => Color::srgb_u8(0, 0, 88),
+        (_, false) => Color::srgb_u8(0, 88, 0),
+        // Blue and orange stay apart for every kind of colour blindness.
+        (1, true) => Color::srgb_u8(0, 114, 178),
+        (_, true) => Color::srgb_u8(230, 159, 0),
+    }
+}
+
+/// Drawn in [`player_color`], so it can be repainted when the palette changes.
+#[derive(Component)]
+pub struct PlayerColored(pub usize);
+
 fn spawn_board(
     _trigger: Trigger<SpawnBoard>,
     mut commands: Commands,
@@ -53,24 +103,35 @@ fn spawn_board(
     mut meshes: ResMut<Assets<Mesh>>,
     asset_server: Res<AssetServer>,
     plugin_settings: Res<LaMesaPluginSettings>,
+    territory_settings: Res<TerritorySettings>,
+    drafted_decks: Option<Res<DraftedDecks>>,
+    campaign: Res<Campaign>,
+    active_scenario: Option<Res<ActiveScenario>>,
+    tutorial: Option<Res<Tutorial>>,
+    state: Res<GameState>,
+    settings: Res<Settings>,
 ) {
-    commands.spawn(PointLightBundle {
-        point_light: PointLight {
-            shadows_enabled: true,
+    commands
+        .spawn(PointLightBundle {
+            point_light: PointLight {
+                shadows_enabled: settings.shadows,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 7.0, 7.0),
             ..default()
-        },
-        transform: Transform::from_xyz(0.0, 7.0, 7.0),
-        ..default()
-    });
+        })
+        .insert(StateScoped(Screen::Playing));
 
-    commands.spawn(PointLightBundle {
-        point_light: PointLight {
-            shadows_enabled: true,
+    commands
+        .spawn(PointLightBundle {
+            point_light: PointLight {
+                shadows_enabled: settings.shadows,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 7.0, -7.0),
             ..default()
-        },
-        transform: Transform::from_xyz(0.0, 7.0, -7.0),
-        ..default()
-    });
+        })
+        .insert(StateScoped(Screen::Playing));
 
     // Racign Area
     commands.spawn((
@@ -82,6 +143,7 @@ fn spawn_board(
             ..default()
         },
         RacingCycle,
+        StateScoped(Screen::Playing),
         Name::new("Racing Area"),
     ));
 
@@ -90,38 +152,35 @@ fn spawn_board(
     commands.spawn((
         PbrBundle {
             mesh: sphere.clone(),
-            material: materials.add(Color::srgb_u8(0, 0, 88)),
+            material: materials.add(player_color(1, settings.colour_blind)),
             transform: Transform::from_translation(Vec3::new(-7.6, 0.0, 0.0))
                 .with_scale(Vec3::ONE * 0.3),
             ..default()
         },
-        RacingGem { player: 1 },
+        RacingGem {
+            player: 1,
+            balance: 0,
+        },
+        PlayerColored(1),
+        StateScoped(Screen::Playing),
         Name::new("Racing Gem 1"),
     ));
 
     commands.spawn((
         PbrBundle {
             mesh: sphere.clone(),
-            material: materials.add(Color::srgb_u8(0, 88, 0)),
+            material: materials.add(player_color(2, settings.colour_blind)),
             transform: Transform::from_translation(Vec3::new(-7.6, 0.0, 0.0))
                 .with_scale(Vec3::ONE * 0.3),
             ..default()
         },
-        RacingGem { player: 2 },
-        Name::new("Racing Gem 2"),
-    ));
-
-    // Deck Area - Play Cards
-    commands.spawn((
-        PbrBundle {
-            mesh: meshes.add(Plane3d::default().mesh().size(2.5, 3.5).subdivisions(10)),
-            material: materials.add(Color::BLACK),
-            transform: Transform::from_translation(Vec3::new(-7.6, 0.0, 0.0))
-                .with_rotation(Quat::from_rotation_y(std::f32::consts::PI / 2.0)),
-            ..default()
+        RacingGem {
+            player: 2,
+            balance: 0,
         },
-        DeckArea { marker: 1 },
-        Name::new("Deck 1 -- Play Cards"),
+        PlayerColored(2),
+        StateScoped(Screen::Playing),
+        Name::new("Racing Gem 2"),
     ));
 
     // Deck Area - Event Cards
@@ -134,6 +193,7 @@ fn spawn_board(
             ..default()
         },
         DeckArea { marker: 2 },
+        StateScoped(Screen::Playing),
         Name::new("Deck 2 - Event Cards"),
     ));
 
@@ -163,6 +223,7 @@ fn spawn_board(
             )),
             ..default()
         },
+        StateScoped(Screen::Playing),
         Name::new("Resources - Production - Player 1"),
     ));
 
@@ -183,6 +244,7 @@ fn spawn_board(
             .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
             ..default()
         },
+        StateScoped(Screen::Playing),
         Name::new("Resources - Production - Player 2"),
     ));
 
@@ -209,6 +271,7 @@ fn spawn_board(
             )),
             ..default()
         },
+        StateScoped(Screen::Playing),
         Name::new("Resources - Sales - Player 1"),
     ));
 
@@ -229,6 +292,7 @@ fn spawn_board(
             .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
             ..default()
         },
+        StateScoped(Screen::Playing),
         Name::new("Resources - Sales - Player 2"),
     ));
 
@@ -252,6 +316,7 @@ fn spawn_board(
             marker: 6,
             player: 1,
         },
+        StateScoped(Screen::Playing),
         Name::new("Event Card - Player 1"),
     ));
 
@@ -268,6 +333,7 @@ fn spawn_board(
             marker: 6,
             player: 2,
         },
+        StateScoped(Screen::Playing),
         Name::new("Event Card - Player 1"),
     ));
 
@@ -283,6 +349,7 @@ fn spawn_board(
             marker: 1,
             player: 1,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 1 - Player 1"),
     ));
 
@@ -298,6 +365,7 @@ fn spawn_board(
             marker: 2,
             player: 1,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 2 - Player 2"),
     ));
 
@@ -313,6 +381,7 @@ fn spawn_board(
             marker: 3,
             player: 1,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 3 - Player 3"),
     ));
 
@@ -328,6 +397,7 @@ fn spawn_board(
             marker: 4,
             player: 1,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 4 - Player 4"),
     ));
 
@@ -343,6 +413,7 @@ fn spawn_board(
             marker: 5,
             player: 1,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 5 - Player 5"),
     ));
 
@@ -361,6 +432,7 @@ fn spawn_board(
             marker: 5,
             player: 2,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 1 - Player 2"),
     ));
 
@@ -377,6 +449,7 @@ fn spawn_board(
             marker: 4,
             player: 2,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 2 - Player 2"),
     ));
 
@@ -393,6 +466,7 @@ fn spawn_board(
             marker: 3,
             player: 2,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 3 - Player 2"),
     ));
 
@@ -409,6 +483,7 @@ fn spawn_board(
             marker: 2,
             player: 2,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 4 - Player 2"),
     ));
 
@@ -425,13 +500,105 @@ fn spawn_board(
             marker: 1,
             player: 2,
         },
+        StateScoped(Screen::Playing),
         Name::new("Play Area 5 - Player 2"),
     ));
 
-    ew_render_deck.send(RenderDeck::<Kard> {
-        marker: 1,
-        deck: load_playing_deck(plugin_settings.num_players),
-    });
+    // Territory Map
+    if territory_settings.enabled {
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Plane3d::default().mesh().size(3.6, 3.6)),
+                material: materials.add(Color::srgb(0.15, 0.15, 0.15)),
+                transform: Transform::from_translation(TERRITORY_MAP_POSITION),
+                ..default()
+            },
+            StateScoped(Screen::Playing),
+            Name::new("Territory Map"),
+        ));
+
+        for region in Region::ALL {
+            let definition = region.definition();
+            let (x, z) = definition.position;
+            commands.spawn((
+                PbrBundle {
+                    mesh: meshes.add(Cuboid::new(1.5, 0.05, 1.5)),
+                    material: materials.add(definition.color),
+                    transform: Transform::from_translation(
+                        TERRITORY_MAP_POSITION + Vec3::new(x, 0.025, z),
+                    ),
+                    ..default()
+                },
+                StateScoped(Screen::Playing),
+                Name::new(format!("Region - {}", definition.name)),
+            ));
+        }
+    }
+
+    // Personal Decks - drafted, the scenario's, the tutorial's, or the same
+    // starting deck for everyone
+    let scenario = active_scenario.and_then(|active| campaign.scenarios.get(active.0));
+    let decks = match (drafted_decks, scenario) {
+        (Some(drafted_decks), _) => drafted_decks.0.clone(),
+        (None, Some(scenario)) => (0..plugin_settings.num_players)
+            .map(|_| scenario.load_deck())
+            .collect(),
+        (None, None) if tutorial.is_some() => (0..plugin_settings.num_players)
+            .map(|_| load_tutorial_deck())
+            .collect(),
+        (None, None) => (0..plugin_settings.num_players)
+            .map(|_| {
+                let mut deck = load_starting_deck();
+                if territory_settings.enabled {
+                    deck.extend(load_influence_cards(1));
+                }
+                deck
+            })
+            .collect(),
+    };
+
+    for (index, deck) in decks.into_iter().enumerate() {
+        let player = index + 1;
+        let (z, rotation) = match player {
+            1 => (7.0, 0.0),
+            _ => (-7.0, std::f32::consts::PI),
+        };
+
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Plane3d::default().mesh().size(2.5, 3.5).subdivisions(10)),
+                material: materials.add(Color::BLACK),
+                transform: Transform::from_translation(Vec3::new(-7.6 + 3.05 * 5.0, 0.0, z))
+                    .with_rotation(Quat::from_rotation_y(rotation)),
+                ..default()
+            },
+            DeckArea {
+                marker: state.deck_marker(player),
+            },
+            StateScoped(Screen::Playing),
+            Name::new(format!("Deck - Player {}", player)),
+        ));
+
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Plane3d::default().mesh().size(2.5, 3.5).subdivisions(10)),
+                material: materials.add(Color::srgb(0.2, 0.2, 0.2)),
+                transform: Transform::from_translation(Vec3::new(-7.6 + 3.05 * 6.0, 0.0, z))
+                    .with_rotation(Quat::from_rotation_y(rotation)),
+                ..default()
+            },
+            DeckArea {
+                marker: state.discard_marker(player),
+            },
+            StateScoped(Screen::Playing),
+            Name::new(format!("Discard Pile - Player {}", player)),
+        ));
+
+        ew_render_deck.send(RenderDeck::<Kard> {
+            marker: state.deck_marker(player),
+            deck,
+        });
+    }
 
     ew_render_deck.send(RenderDeck::<Kard> {
         marker: 2,
@@ -444,11 +611,14 @@ pub fn handle_drop_chip(
     mut er_drop_chip: EventReader<DropChip>,
     query: Query<(Entity, &ChipArea, &Chip<ChipType>)>,
     game_state: Res<GameState>,
-    chip_model_handles: Res<HandleMap<ChipModel>>,
+    chip_model_handles: Res<HandleMap<ChipType>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    settings: Res<Settings>,
 ) {
-    let mut cocaine_counter = 0;
-    let mut cannabis_counter = 0;
+    let mut counters = vec![0; PRODUCTS.len()];
     for drop_chip in er_drop_chip.read() {
+        let product = drop_chip.chip_type.product();
         let num_chips_of_kind = query
             .iter()
             .filter(|(_, area, chip)| {
@@ -458,54 +628,27 @@ pub fn handle_drop_chip(
             })
             .count();
 
-        let model = match drop_chip.chip_type {
-            ChipType::Cannabis => chip_model_handles.get(&ChipModel::Cannabis).unwrap(),
-            ChipType::Cocaine => chip_model_handles.get(&ChipModel::Cocaine).unwrap(),
-        };
-
         let delta = 2.0;
 
-        let mut initial_translation = match drop_chip.chip_type {
-            ChipType::Cannabis => {
-                Transform::from_xyz(0.6 + delta, 12.0, 1.5).with_scale(Vec3::ONE * 1.0)
-            }
-            ChipType::Cocaine => {
-                Transform::from_xyz(1.8 + delta, 12.0, 3.3).with_scale(Vec3::ONE * 1.0)
-            }
-        }
-        .translation;
+        let (x, z) = product.stack_position;
+        let mut initial_translation = Vec3::new(x + delta, 12.0, z);
         initial_translation.z *= if drop_chip.player == 1 { 1.0 } else { -1.0 };
 
         let mut final_translation = initial_translation;
-        final_translation.y = 0.1
-            + (match drop_chip.chip_type {
-                ChipType::Cannabis => cannabis_counter,
-                ChipType::Cocaine => cocaine_counter,
-            } + num_chips_of_kind) as f32
-                * 0.2;
+        final_translation.y =
+            0.1 + (counters[drop_chip.chip_type.0] + num_chips_of_kind) as f32 * 0.2;
 
         let tween: Tween<Transform> = Tween::new(
             EaseFunction::QuadraticIn,
-            Duration::from_millis(350),
+            settings.animation(Duration::from_millis(350)),
             TransformPositionLens {
                 start: initial_translation,
                 end: final_translation,
             },
         );
 
-        commands.spawn((
-            SceneBundle {
-                scene: model.clone(),
-                transform: match drop_chip.chip_type {
-                    ChipType::Cannabis => {
-                        Transform::from_xyz(0.6, 12.0, -5.2).with_scale(Vec3::ONE * 1.0)
-                    }
-                    ChipType::Cocaine => {
-                        Transform::from_xyz(1.8, 12.0, -3.6).with_scale(Vec3::ONE * 1.0)
-                    }
-                },
-                ..default()
-            },
+        let chip = (
+            StateScoped(Screen::Playing),
             Name::new("Chip"),
             Chip::<ChipType> {
                 data: drop_chip.chip_type,
@@ -517,12 +660,33 @@ pub fn handle_drop_chip(
                 marker: drop_chip.area,
             },
             Animator::new(tween),
-        ));
+        );
 
-        match drop_chip.chip_type {
-            ChipType::Cannabis => cannabis_counter += 1,
-            ChipType::Cocaine => cocaine_counter += 1,
+        match chip_model_handles.get(&drop_chip.chip_type) {
+            Some(model) => {
+                commands.spawn((
+                    SceneBundle {
+                        scene: model.clone(),
+                        transform: Transform::from_translation(initial_translation),
+                        ..default()
+                    },
+                    chip,
+                ));
+            }
+            None => {
+                commands.spawn((
+                    PbrBundle {
+                        mesh: meshes.add(Cylinder::new(0.4, 0.15)),
+                        material: materials.add(product.color),
+                        transform: Transform::from_translation(initial_translation),
+                        ..default()
+                    },
+                    chip,
+                ));
+            }
         }
+
+        counters[drop_chip.chip_type.0] += 1;
     }
 }
 
@@ -530,9 +694,9 @@ pub fn handle_move_chip_to_sales(
     mut commands: Commands,
     mut er_move_chip: EventReader<MoveChip>,
     query: Query<(Entity, &Transform, &ChipArea, &Chip<ChipType>)>,
+    settings: Res<Settings>,
 ) {
-    let mut n_cocaine_chips_moved = 0;
-    let mut n_cannabis_chips_moved = 0;
+    let mut n_chips_moved = vec![0; PRODUCTS.len()];
     for (_i, move_chip) in er_move_chip.read().enumerate() {
         let chip = query.get(move_chip.entity).unwrap();
         let chip_type = chip.3.data;
@@ -544,17 +708,11 @@ pub fn handle_move_chip_to_sales(
 
         let mut final_translation = initial_translation;
         final_translation.x += 3.3;
-        final_translation.y = 0.1
-            + (num_chips_of_kind
-                + match chip_type {
-                    ChipType::Cannabis => n_cannabis_chips_moved,
-                    ChipType::Cocaine => n_cocaine_chips_moved,
-                }) as f32
-                * 0.2;
+        final_translation.y = 0.1 + (num_chips_of_kind + n_chips_moved[chip_type.0]) as f32 * 0.2;
 
         let tween: Tween<Transform> = Tween::new(
             EaseFunction::QuadraticIn,
-            Duration::from_millis(350),
+            settings.animation(Duration::from_millis(350)),
             TransformPositionLens {
                 start: initial_translation,
                 end: final_translation,
@@ -569,42 +727,168 @@ pub fn handle_move_chip_to_sales(
                 player: move_chip.player,
             });
 
-        match chip_type {
-            ChipType::Cannabis => n_cannabis_chips_moved += 1,
-            ChipType::Cocaine => n_cocaine_chips_moved += 1,
-        }
+        n_chips_moved[chip_type.0] += 1;
+    }
+}
+
+/// Slides traded chips across the table onto the receiver's stack.
+pub fn handle_transfer_chip(
+    mut commands: Commands,
+    mut er_transfer_chip: EventReader<TransferChip>,
+    query: Query<(Entity, &Transform, &ChipArea, &Chip<ChipType>)>,
+    settings: Res<Settings>,
+) {
+    let mut n_chips_moved = vec![0; PRODUCTS.len()];
+    for transfer_chip in er_transfer_chip.read() {
+        let Ok((_, transform, area, chip)) = query.get(transfer_chip.entity) else {
+            continue;
+        };
+        let chip_type = chip.data;
+        let marker = area.marker;
+        let num_chips_of_kind = query
+            .iter()
+            .filter(|(_, _, area, chip)| {
+                area.player == transfer_chip.player
+                    && area.marker == marker
+                    && chip.data == chip_type
+            })
+            .count();
+
+        let initial_translation = transform.translation;
+        let mut final_translation = initial_translation;
+        final_translation.z = -initial_translation.z;
+        final_translation.y = 0.1 + (num_chips_of_kind + n_chips_moved[chip_type.0]) as f32 * 0.2;
+
+        let tween: Tween<Transform> = Tween::new(
+            EaseFunction::QuadraticInOut,
+            settings.animation(Duration::from_millis(350)),
+            TransformPositionLens {
+                start: initial_translation,
+                end: final_translation,
+            },
+        );
+
+        commands
+            .entity(transfer_chip.entity)
+            .insert(Animator::new(tween))
+            .insert(ChipArea {
+                marker,
+                player: transfer_chip.player,
+            });
+
+        n_chips_moved[chip_type.0] += 1;
     }
 }
 
 pub fn discard_chip(
     mut commands: Commands,
     mut er_discard_chip: EventReader<DiscardChip>,
-    _query: Query<(Entity, &Transform, &Chip<ChipType>)>,
+    query: Query<&Transform, With<Chip<ChipType>>>,
+    settings: Res<Settings>,
 ) {
     for discard_chip in er_discard_chip.read() {
-        if commands.get_entity(discard_chip.entity).is_none() {
+        let Ok(transform) = query.get(discard_chip.entity) else {
+            continue;
+        };
+
+        let mut final_translation = transform.translation;
+        final_translation.y = 12.0;
+
+        let tween: Tween<Transform> = Tween::new(
+            EaseFunction::QuadraticIn,
+            settings.animation(Duration::from_millis(350)),
+            TransformPositionLens {
+                start: transform.translation,
+                end: final_translation,
+            },
+        )
+        .with_completed_event(DISCARDED_CHIP_TWEEN);
+
+        commands
+            .entity(discard_chip.entity)
+            .insert(Animator::new(tween))
+            .remove::<ChipArea>();
+    }
+}
+
+/// Flies sold chips from the sales area to the seller's racing gem, which
+/// stands for their bank.
+pub fn sell_chip(
+    mut commands: Commands,
+    mut er_chip_sold: EventReader<ChipSold>,
+    chips: Query<&Transform, With<Chip<ChipType>>>,
+    gems: Query<(&RacingGem, &Transform), Without<Chip<ChipType>>>,
+    settings: Res<Settings>,
+) {
+    for (index, event) in er_chip_sold.read().enumerate() {
+        let Ok(transform) = chips.get(event.entity) else {
+            continue;
+        };
+        let Some((_, gem_transform)) = gems.iter().find(|(gem, _)| gem.player == event.player)
+        else {
+            continue;
+        };
+
+        // Chips sold together leave one after another.
+        let tween: Tween<Transform> = Tween::new(
+            EaseFunction::QuadraticInOut,
+            settings.animation(Duration::from_millis(500 + 80 * index as u64)),
+            TransformPositionLens {
+                start: transform.translation,
+                end: gem_transform.translation,
+            },
+        )
+        .with_completed_event(SOLD_CHIP_TWEEN);
+
+        commands
+            .entity(event.entity)
+            .insert(Animator::new(tween))
+            .remove::<ChipArea>();
+    }
+}
+
+pub fn seize_chip(
+    mut commands: Commands,
+    mut er_seize_chip: EventReader<SeizeChip>,
+    query: Query<&Transform, With<Chip<ChipType>>>,
+    settings: Res<Settings>,
+) {
+    for seize_chip in er_seize_chip.read() {
+        let Ok(transform) = query.get(seize_chip.entity) else {
+            continue;
+        };
+
+        let mut final_translation = transform.translation;
+        final_translation.y = 20.0;
+
+        let tween: Tween<Transform> = Tween::new(
+            EaseFunction::QuadraticIn,
+            settings.animation(Duration::from_millis(700)),
+            TransformPositionLens {
+                start: transform.translation,
+                end: final_translation,
+            },
+        )
+        .with_completed_event(SEIZED_CHIP_TWEEN);
+
+        commands
+            .entity(seize_chip.entity)
+            .insert(Animator::new(tween))
+            .remove::<ChipArea>();
+    }
+}
+
+pub fn despawn_tweened_chips(
+    mut commands: Commands,
+    mut er_tween_completed: EventReader<TweenCompleted>,
+) {
+    for event in er_tween_completed.read() {
+        if ![SEIZED_CHIP_TWEEN, SOLD_CHIP_TWEEN, DISCARDED_CHIP_TWEEN].contains(&event.user_data) {
             continue;
         }
-        commands.entity(discard_chip.entity).despawn_recursive();
-        // let chip = query.get(discard_chip.entity).unwrap();
-        // let initial_translation = chip.1.translation;
-
-        // let mut final_translation = initial_translation;
-        // final_translation.y = 120.0;
-
-        // let tween: Tween<Transform> = Tween::new(
-        //     EaseFunction::QuadraticIn,
-        //     Duration::from_millis(350),
-        //     TransformPositionLens {
-        //         start: initial_translation,
-        //         end: final_translation,
-        //     },
-        // );
-
-        // commands
-        //     .entity(discard_chip.entity)
-        //     .insert(Animator::new(tween))
-        //     .remove::<ChipArea>();
+        if let Some(entity) = commands.get_entity(event.entity) {
+            entity.despawn_recursive();
+        }
     }
 }
 
@@ -632,22 +916,141 @@ pub fn render_hand_area(mut commands: Commands) {
     ));
 }
 
+/// Slides each racing gem around the lap to its player's balance.
 pub fn update_race_gem_positions(
-    mut query: Query<(Entity, &RacingGem, &mut Transform)>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut RacingGem)>,
     game_state: Res<GameState>,
+    settings: Res<Settings>,
 ) {
-    let goal = 50000.0;
-    for (_, gem, mut transform) in query.iter_mut() {
+    let goal = game_state.target_bank as f32;
+    for (entity, mut gem) in query.iter_mut() {
+        let balance = game_state.get_balance(gem.player);
+        if balance == gem.balance {
+            continue;
+        }
+
         let radius = match gem.player {
             1 => 4.2,
             _ => 4.8,
         };
+        let angle = |balance: i32| -(balance as f32) / goal * std::f32::consts::PI * 2.0;
+        let position =
+            |angle: f32| Vec3::new(-7.6 + radius * angle.cos(), 0.0, radius * angle.sin());
+
+        // Short straight steps, so the gem follows the ring instead of
+        // cutting across it.
+        let (from, to) = (angle(gem.balance), angle(balance));
+        let steps = ((to - from).abs() / RACING_GEM_STEP).ceil().max(1.0) as u32;
+        let step_duration =
+            settings.animation(Duration::from_millis(RACING_GEM_MILLIS / steps as u64));
+        let tweens: Vec<Tween<Transform>> = (0..steps)
+            .map(|step| {
+                let start = from + (to - from) * step as f32 / steps as f32;
+                let end = from + (to - from) * (step + 1) as f32 / steps as f32;
+                Tween::new(
+                    EaseMethod::Linear,
+                    step_duration.max(Duration::from_millis(1)),
+                    TransformPositionLens {
+                        start: position(start),
+                        end: position(end),
+                    },
+                )
+            })
+            .collect();
+
+        commands
+            .entity(entity)
+            .insert(Animator::new(Sequence::new(tweens)));
+        gem.balance = balance;
+    }
+}
+
+/// Puts a token next to the player's sales board for each technology they own.
+pub fn spawn_technology_tokens(
+    mut commands: Commands,
+    mut er_researched: EventReader<TechnologyResearched>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for event in er_researched.read() {
+        let index = Technology::ALL
+            .iter()
+            .position(|technology| *technology == event.technology)
+            .unwrap_or_default();
+        let z = 0.8 + index as f32 * 0.8;
+        let z = match event.player {
+            1 => z,
+            _ => -z,
+        };
+
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Cuboid::new(0.5, 0.1, 0.5)),
+                material: materials.add(event.technology.definition().color),
+                transform: Transform::from_xyz(8.5, 0.05, z),
+                ..default()
+            },
+            StateScoped(Screen::Playing),
+            Name::new(format!(
+                "Technology - {} - Player {}",
+                event.technology.definition().name,
+                event.player
+            )),
+        ));
+    }
+}
+
+/// Stacks a marker in the player's corner of the region tile, in the same
+/// colour as their racing gem.
+pub fn spawn_influence_markers(
+    mut commands: Commands,
+    mut er_influence_placed: EventReader<InfluencePlaced>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    settings: Res<Settings>,
+) {
+    for event in er_influence_placed.read() {
+        let (x, z) = event.region.definition().position;
+        let corner = match event.player {
+            1 => 0.4,
+            _ => -0.4,
+        };
 
-        let current_score: i32 = game_state.get_balance(gem.player);
-        let percent_of_lap = (current_score as f32) / goal;
-        let angle = -percent_of_lap * std::f32::consts::PI * 2.0;
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Cylinder::new(0.2, 0.1)),
+                material: materials.add(player_color(event.player, settings.colour_blind)),
+                transform: Transform::from_translation(
+                    TERRITORY_MAP_POSITION
+                        + Vec3::new(x, 0.05 + event.markers as f32 * 0.1, z + corner),
+                ),
+                ..default()
+            },
+            PlayerColored(event.player),
+            StateScoped(Screen::Playing),
+            Name::new(format!(
+                "Influence - {} - Player {}",
+                event.region.definition().name,
+                event.player
+            )),
+        ));
+    }
+}
 
-        transform.translation.x = -7.6 + radius * angle.cos();
-        transform.translation.z = radius * angle.sin();
+/// Repaints everything in a player's colour when the palette changes.
+fn repaint_player_colors(
+    settings: Res<Settings>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    meshes: Query<(&PlayerColored, &Handle<StandardMaterial>)>,
+    mut nodes: Query<(&PlayerColored, &mut BackgroundColor)>,
+) {
+    for (colored, handle) in &meshes {
+        if let Some(material) = materials.get_mut(handle) {
+            material.base_color = player_color(colored.0, settings.colour_blind);
+        }
+    }
+    for (colored, mut background) in &mut nodes {
+        background.0 = player_color(colored.0, settings.colour_blind);
     }
 }
@@ -1,111 +1,345 @@
 //! Spawn the main level by triggering other observers.
 
-use bevy::prelude::*;
-use bevy_la_mesa::events::{CardPress, PlaceCardOnTable};
+use bevy::{
+    ecs::system::EntityCommands, prelude::*, ui::RelativeCursorPosition, window::PrimaryWindow,
+};
+use bevy_la_mesa::events::CardPress;
 use bevy_la_mesa::{Card, CardOnTable, Hand};
 
-use crate::game::cards::{GameState, Kard};
-use crate::screen::Screen;
-use crate::ui::widgets::Widgets;
+use crate::game::ai::AiPlayers;
+use crate::game::cards::{CardRejected, GameState, Kard, PlayBlocker, TurnPhase};
+use crate::game::effects::EffectType;
+use crate::game::loans::LOAN_AMOUNT;
+use crate::game::placement::{Drag, DraggedCard};
+use crate::game::research::Technology;
+use crate::game::settings::Settings;
+use crate::game::territory::Region;
+use crate::game::trade::{TradeAction, TradeItem, TRADE_CASH_STEP};
+use crate::game::tutorial::Tutorial;
+use crate::screen::{Pause, Screen};
+use crate::ui::{palette::*, widgets::Widgets};
 
-use super::level::SpawnBoard;
+use super::level::{player_color, PlayerColored, SpawnBoard};
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 pub enum CardGameUIAction {
     ButtonShuffleDeck,
     ButtonDrawHand,
-    ButtonDropChip,
-    ButtonMoveChip,
     ButtonAdvancePhase,
-    ButtonSwitchPlayer,
-    LabelPlayerNumber,
     LabelTurnNumber,
-    LabelTurnPhase,
     LabelPhaseDescription,
-    LabelBank,
-    LabelEffects,
-    LabelGameOver,
-    ContainerGameOver,
+    LabelObjectives,
+    ContainerPlayerPanel(usize),
+    LabelPlayerName(usize),
+    LabelPlayerBank(usize),
+    LabelPlayerChips(usize),
+    LabelDeck,
+    ButtonTakeLoan,
+    ButtonRepayLoan,
+    ButtonResearch(Technology),
+    LabelTerritory,
+    ButtonInfluence(Region),
+    LabelTrade,
+    ButtonTrade(TradeAction),
+    LabelTradeOffer,
+    ContainerTradeOffer,
+    LabelLedger,
+    ContainerTransactions,
+}
+
+/// Row of badges for the effects on a player.
+#[derive(Component)]
+pub struct EffectBadges {
+    pub player: usize,
+    pub shown: Vec<(EffectType, usize)>,
+}
+
+/// One step of the phase tracker.
+#[derive(Component)]
+pub struct PhaseStep(pub TurnPhase);
+
+/// Part of the current player's table a group of actions sits next to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableAnchor {
+    Deck,
+    Hand,
+}
+
+/// Playability marker floating over one of the current player's hand cards.
+#[derive(Component)]
+pub struct HandCardBadge {
+    pub card: Entity,
+}
+
+/// Short message that disappears once its timer runs out.
+#[derive(Component)]
+pub struct Toast(pub Timer);
+
+/// Money earned by a sale, rising from the sales area as it fades.
+#[derive(Component)]
+pub struct PayoutNumber(pub Timer);
+
+/// Scrollable list of the current player's transactions.
+#[derive(Component, Default)]
+pub struct LedgerList {
+    pub player: usize,
+    pub len: usize,
+    pub position: f32,
 }
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_card_game_ui)
-        .add_systems(Update, handle_card_press);
+        .add_systems(Update, handle_card_press.run_if(in_state(Pause::Running)));
 }
 
-fn spawn_card_game_ui(_trigger: Trigger<SpawnBoard>, mut commands: Commands) {
+fn spawn_card_game_ui(
+    _trigger: Trigger<SpawnBoard>,
+    mut commands: Commands,
+    state: Res<GameState>,
+    settings: Res<Settings>,
+) {
     commands
         .spawn((
-            Name::new("UI Root"),
+            Name::new("Phase Tracker"),
             NodeBundle {
                 style: Style {
-                    width: Val::Px(216.0),
-                    height: Val::Percent(100.0),
+                    width: Val::Percent(100.0),
+                    top: Val::Px(8.0),
                     justify_content: JustifyContent::Center,
-                    align_items: AlignItems::FlexEnd,
-                    flex_direction: FlexDirection::Column,
-                    row_gap: Val::Px(10.0),
-                    right: Val::Px(0.0),
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(4.0),
                     position_type: PositionType::Absolute,
                     ..default()
                 },
-                background_color: BackgroundColor::from(Color::BLACK),
                 ..default()
             },
         ))
         .insert(StateScoped(Screen::Playing))
         .with_children(|children| {
             children
-                .label("Turn number: 1")
+                .label("Turn 1")
                 .insert(CardGameUIAction::LabelTurnNumber);
+            for phase in TurnPhase::ALL {
+                children
+                    .spawn((
+                        Name::new(format!("Phase - {}", phase.name())),
+                        NodeBundle {
+                            style: Style {
+                                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(HUD_PHASE_UPCOMING),
+                            border_radius: BorderRadius::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        PhaseStep(phase),
+                    ))
+                    .with_children(|children| {
+                        children.spawn(TextBundle::from_section(
+                            phase.name(),
+                            TextStyle {
+                                font_size: 14.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+
+    commands
+        .spawn((
+            Name::new("HUD"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(232.0),
+                    top: Val::Px(44.0),
+                    right: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .insert(StateScoped(Screen::Playing))
+        .with_children(|children| {
+            for player in 1..=state.num_players() {
+                spawn_player_panel(children, player, settings.colour_blind);
+            }
+
             children
-                .label("Player number: 1")
-                .insert(CardGameUIAction::LabelPlayerNumber);
-            children
-                .label("Turn phase: Prepare")
-                .insert(CardGameUIAction::LabelTurnPhase);
-            children
-                .label("Phase Description")
-                .insert(CardGameUIAction::LabelPhaseDescription);
-            children
-                .label("Effects")
-                .insert(CardGameUIAction::LabelEffects);
-            children
-                .label("Bank: $0")
-                .insert(CardGameUIAction::LabelBank);
-            // children
-            //     .button("Switch Player")
-            //     .insert(CardGameUIAction::ButtonSwitchPlayer);
-            children
-                .button("Shuffle Deck")
-                .insert(CardGameUIAction::ButtonShuffleDeck);
-            children
-                .button("Draw Hand")
-                .insert(CardGameUIAction::ButtonDrawHand);
+                .spawn((
+                    Name::new("Management"),
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            align_items: AlignItems::Center,
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(6.0),
+                            padding: UiRect::all(Val::Px(8.0)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(HUD_PANEL_BACKGROUND),
+                        border_radius: BorderRadius::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|children| {
+                    children
+                        .label("Objectives")
+                        .insert(CardGameUIAction::LabelObjectives);
+                    children
+                        .label("Ledger")
+                        .insert(CardGameUIAction::LabelLedger);
+                    children
+                        .button(format!("Take Loan (${})", LOAN_AMOUNT))
+                        .insert(CardGameUIAction::ButtonTakeLoan);
+                    children
+                        .button(format!("Repay Loan (${})", LOAN_AMOUNT))
+                        .insert(CardGameUIAction::ButtonRepayLoan);
+                    for technology in Technology::ALL {
+                        let definition = technology.definition();
+                        children
+                            .button(format!("{} (${})", definition.name, definition.cost))
+                            .insert(CardGameUIAction::ButtonResearch(technology));
+                    }
+                    children
+                        .label("Territory")
+                        .insert(CardGameUIAction::LabelTerritory);
+                    for region in Region::ALL {
+                        children
+                            .button(format!("Influence {}", region.definition().name))
+                            .insert(CardGameUIAction::ButtonInfluence(region));
+                    }
+                });
+        });
+
+    // Actions follow the current player's deck and hand around the screen.
+    spawn_anchored_actions(&mut commands, TableAnchor::Deck).with_children(|children| {
+        children.label("Deck").insert(CardGameUIAction::LabelDeck);
+        children
+            .button("Shuffle Deck")
+            .insert(CardGameUIAction::ButtonShuffleDeck);
+        children
+            .button("Draw Hand")
+            .insert(CardGameUIAction::ButtonDrawHand);
+    });
+
+    spawn_anchored_actions(&mut commands, TableAnchor::Hand).with_children(|children| {
+        children
+            .label("Phase Description")
+            .insert(CardGameUIAction::LabelPhaseDescription);
+        children
+            .button("Advance Phase")
+            .insert(CardGameUIAction::ButtonAdvancePhase);
+    });
+
+    commands
+        .spawn((
+            Name::new("Ledger"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(216.0),
+                    height: Val::Percent(40.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    left: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK),
+                ..default()
+            },
+        ))
+        .insert(StateScoped(Screen::Playing))
+        .with_children(|children| {
+            children.label("Transactions");
             children
-                .button("Advance Phase")
-                .insert(CardGameUIAction::ButtonAdvancePhase);
-            // children
-            //     .button("Drop Chip")
-            //     .insert(CardGameUIAction::ButtonDropChip);
-            // children
-            //     .button("Move Chip")
-            //     .insert(CardGameUIAction::ButtonMoveChip);
+                .spawn((
+                    Name::new("Transactions"),
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            flex_grow: 1.0,
+                            overflow: Overflow::clip_y(),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    RelativeCursorPosition::default(),
+                    CardGameUIAction::ContainerTransactions,
+                ))
+                .with_children(|children| {
+                    children.spawn((
+                        Name::new("Transaction List"),
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        LedgerList::default(),
+                    ));
+                });
         });
 
-    let text = Text::from_section(
-        "GAME OVER",
-        TextStyle {
-            font_size: 100.0,
-            color: Color::WHITE,
-            ..default()
-        },
-    );
+    commands
+        .spawn((
+            Name::new("Trade"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(216.0),
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK),
+                ..default()
+            },
+        ))
+        .insert(StateScoped(Screen::Playing))
+        .with_children(|children| {
+            children.label("Trade").insert(CardGameUIAction::LabelTrade);
+            for (action, text) in [
+                (
+                    TradeAction::Offer(TradeItem::Cash),
+                    format!("Offer ${}", TRADE_CASH_STEP),
+                ),
+                (
+                    TradeAction::Offer(TradeItem::Chip),
+                    "Offer Chip".to_string(),
+                ),
+                (
+                    TradeAction::Offer(TradeItem::Card),
+                    "Offer Card".to_string(),
+                ),
+                (
+                    TradeAction::Ask(TradeItem::Cash),
+                    format!("Ask ${}", TRADE_CASH_STEP),
+                ),
+                (TradeAction::Ask(TradeItem::Chip), "Ask Chip".to_string()),
+                (TradeAction::Ask(TradeItem::Card), "Ask Card".to_string()),
+                (TradeAction::Propose, "Propose Trade".to_string()),
+                (TradeAction::Clear, "Clear Trade".to_string()),
+            ] {
+                children
+                    .button(text)
+                    .insert(CardGameUIAction::ButtonTrade(action));
+            }
+        });
 
-    // root node
     commands
         .spawn((
+            Name::new("Trade Offer"),
             NodeBundle {
                 style: Style {
                     width: Val::Percent(100.0),
@@ -115,59 +349,192 @@ fn spawn_card_game_ui(_trigger: Trigger<SpawnBoard>, mut commands: Commands) {
                     flex_direction: FlexDirection::Column,
                     row_gap: Val::Px(10.0),
                     position_type: PositionType::Absolute,
-                    ..Default::default()
+                    ..default()
                 },
+                background_color: BackgroundColor::from(Color::srgba(0.0, 0.0, 0.0, 0.7)),
                 visibility: Visibility::Hidden,
-                ..Default::default()
+                ..default()
             },
-            Name::new("Game Over"),
-            CardGameUIAction::ContainerGameOver,
+            CardGameUIAction::ContainerTradeOffer,
         ))
-        .with_children(|parent| {
-            parent
-                .spawn(TextBundle {
-                    text,
-                    ..Default::default()
-                })
-                .insert((CardGameUIAction::LabelGameOver));
+        .insert(StateScoped(Screen::Playing))
+        .with_children(|children| {
+            children
+                .label("Trade Offer")
+                .insert(CardGameUIAction::LabelTradeOffer);
+            children
+                .button("Accept")
+                .insert(CardGameUIAction::ButtonTrade(TradeAction::Accept));
+            children
+                .button("Reject")
+                .insert(CardGameUIAction::ButtonTrade(TradeAction::Reject));
         });
 }
 
+/// Portrait, bank, chips and effects of one player.
+fn spawn_player_panel(children: &mut ChildBuilder, player: usize, colour_blind: bool) {
+    children
+        .spawn((
+            Name::new(format!("Player Panel {}", player)),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(6.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(HUD_PANEL_BACKGROUND),
+                border_color: BorderColor(Color::NONE),
+                border_radius: BorderRadius::all(Val::Px(6.0)),
+                ..default()
+            },
+            CardGameUIAction::ContainerPlayerPanel(player),
+        ))
+        .with_children(|children| {
+            children
+                .spawn((
+                    Name::new("Portrait"),
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(40.0),
+                            height: Val::Px(40.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: BackgroundColor(player_color(player, colour_blind)),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    PlayerColored(player),
+                ))
+                .with_children(|children| {
+                    children.spawn(TextBundle::from_section(
+                        format!("P{}", player),
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                });
+            children
+                .label(format!("Player {}", player))
+                .insert(CardGameUIAction::LabelPlayerName(player));
+            children
+                .label("Bank: $0")
+                .insert(CardGameUIAction::LabelPlayerBank(player));
+            children
+                .label("Chips")
+                .insert(CardGameUIAction::LabelPlayerChips(player));
+            children.spawn((
+                Name::new("Effect Badges"),
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        column_gap: Val::Px(4.0),
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                EffectBadges {
+                    player,
+                    shown: vec![],
+                },
+            ));
+        });
+}
+
+/// Column of buttons kept next to a part of the current player's table.
+fn spawn_anchored_actions<'a>(
+    commands: &'a mut Commands,
+    anchor: TableAnchor,
+) -> EntityCommands<'a> {
+    let mut entity = commands.spawn((
+        Name::new(format!("{:?} Actions", anchor)),
+        NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(HUD_PANEL_BACKGROUND),
+            border_radius: BorderRadius::all(Val::Px(6.0)),
+            ..default()
+        },
+        anchor,
+    ));
+    entity.insert(StateScoped(Screen::Playing));
+    entity
+}
+
+/// Picks up the pressed card; [`placement`](crate::game::placement) plays or
+/// moves it when it's dropped.
 pub fn handle_card_press(
     mut card_press: EventReader<CardPress>,
-    query_cards_in_hand: Query<(Entity, &Card<Kard>, &Hand)>,
-    query_cards_on_table: Query<(Entity, &Card<Kard>, &CardOnTable)>,
-    mut ew_place_card_on_table: EventWriter<PlaceCardOnTable>,
-    mut state: ResMut<GameState>,
+    query_cards_in_hand: Query<&Hand, With<Card<Kard>>>,
+    query_cards_on_table: Query<&CardOnTable, With<Card<Kard>>>,
+    mut ew_card_rejected: EventWriter<CardRejected>,
+    mut dragged_card: ResMut<DraggedCard>,
+    state: Res<GameState>,
+    ai_players: Res<AiPlayers>,
+    tutorial: Option<Res<Tutorial>>,
+    window: Query<&Window, With<PrimaryWindow>>,
 ) {
-    let player = state.player;
     for event in card_press.read() {
-        let hand = query_cards_in_hand.get(event.card_entity).ok();
-        if hand.is_none() {
+        let (player, from_slot) = if let Ok(hand) = query_cards_in_hand.get(event.card_entity) {
+            (hand.player, None)
+        } else if let Ok(on_table) = query_cards_on_table.get(event.card_entity) {
+            (on_table.player, Some(on_table.marker))
+        } else {
             continue;
-        }
-        let (_, kard, hand) = hand.unwrap();
+        };
 
-        if kard.data.price > state.get_balance(state.player) {
+        if tutorial
+            .as_ref()
+            .is_some_and(|tutorial| !tutorial.allows_card_press(from_slot.is_some()))
+        {
             continue;
         }
 
-        state.change_balance(player, -kard.data.price);
-        let markers: Vec<usize> = query_cards_on_table
-            .iter()
-            .filter(|(_, _, t)| t.player == hand.player)
-            .map(|(_, _, t)| t.marker)
-            .collect();
-
-        let marker = markers.iter().max().unwrap_or(&0) + 1;
-        if marker > 5 {
+        // Presses only come from the mouse and the focus cursor, so never
+        // from the seat of a computer player.
+        let checked = if ai_players.0.contains(&player) {
+            Err(PlayBlocker::NotYourTurn)
+        } else {
+            state.check_table_change(player)
+        };
+        if let Err(blocker) = checked {
+            // Cards in play are only looked at outside the play phase.
+            if from_slot.is_none() {
+                ew_card_rejected.send(CardRejected {
+                    card_entity: event.card_entity,
+                    blocker,
+                });
+            }
             continue;
         }
 
-        ew_place_card_on_table.send(PlaceCardOnTable {
+        dragged_card.0 = Some(Drag {
             card_entity: event.card_entity,
-            marker,
-            player: hand.player,
+            player,
+            from_slot,
+            cursor_start: window
+                .get_single()
+                .ok()
+                .and_then(Window::cursor_position)
+                .unwrap_or_default(),
+            moved: false,
+            snap: None,
         });
     }
 }
@@ -0,0 +1,138 @@
+//! Territory map expansion. Influence cards put markers on regions; the
+//! player with the most markers in a region controls it and gets its
+//! modifiers through [`GameState::modify_stat`].
+
+use bevy::prelude::*;
+
+use super::{
+    cards::{CardType, GameState, Kard},
+    effects::{Modifier, Stat},
+};
+
+/// Turns the territory map on or off. Without it the game is the base race.
+/// Off unless chosen on the title screen.
+#[derive(Resource, Default)]
+pub struct TerritorySettings {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum Region {
+    Harbour,
+    Downtown,
+    Border,
+    Suburbs,
+}
+
+pub struct RegionDefinition {
+    pub name: &'static str,
+    /// Offset of the region tile from the centre of the map.
+    pub position: (f32, f32),
+    pub color: Color,
+    pub modifiers: &'static [Modifier],
+}
+
+impl Region {
+    pub const ALL: [Region; 4] = [
+        Region::Harbour,
+        Region::Downtown,
+        Region::Border,
+        Region::Suburbs,
+    ];
+
+    pub fn index(&self) -> usize {
+        Region::ALL
+            .iter()
+            .position(|region| region == self)
+            .unwrap_or_default()
+    }
+
+    pub fn definition(&self) -> &'static RegionDefinition {
+        match self {
+            Region::Harbour => &RegionDefinition {
+                name: "Harbour",
+                position: (-0.8, -0.8),
+                color: Color::srgb(0.3, 0.45, 0.6),
+                modifiers: &[Modifier::Add(Stat::InterceptionRisk, -4)],
+            },
+            Region::Downtown => &RegionDefinition {
+                name: "Downtown",
+                position: (0.8, -0.8),
+                color: Color::srgb(0.55, 0.55, 0.55),
+                modifiers: &[Modifier::Percent(Stat::LocalMarketPrice, 25)],
+            },
+            Region::Border => &RegionDefinition {
+                name: "Border",
+                position: (-0.8, 0.8),
+                color: Color::srgb(0.6, 0.5, 0.3),
+                modifiers: &[Modifier::Percent(Stat::InterceptionRisk, -25)],
+            },
+            Region::Suburbs => &RegionDefinition {
+                name: "Suburbs",
+                position: (0.8, 0.8),
+                color: Color::srgb(0.4, 0.6, 0.35),
+                modifiers: &[
+                    Modifier::Percent(Stat::LocalMarketPrice, 10),
+                    Modifier::Add(Stat::InterceptionRisk, -2),
+                ],
+            },
+        }
+    }
+}
+
+pub fn load_influence_cards(num_players: usize) -> Vec<Kard> {
+    // No art yet; uses the debug face until a card is drawn.
+    let influence = Kard {
+        card_type: CardType::Influence,
+        price: 500,
+        filename: "tarjetas/debug.png".to_string(),
+        flavour: "Friends in all the right places.".to_string(),
+    };
+
+    vec![influence; num_players * 2]
+}
+
+/// Spends one of the player's pending markers on a region.
+#[derive(Event)]
+pub struct PlaceInfluence {
+    pub player: usize,
+    pub region: Region,
+}
+
+/// Sent once a marker is on the map, so the board can show it.
+#[derive(Event)]
+pub struct InfluencePlaced {
+    pub player: usize,
+    pub region: Region,
+    /// Markers the player has in the region, counting this one.
+    pub markers: usize,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TerritorySettings>()
+        .add_event::<PlaceInfluence>()
+        .add_event::<InfluencePlaced>()
+        .add_systems(Update, handle_place_influence);
+}
+
+fn handle_place_influence(
+    mut er_place_influence: EventReader<PlaceInfluence>,
+    mut ew_influence_placed: EventWriter<InfluencePlaced>,
+    mut state: ResMut<GameState>,
+) {
+    for event in er_place_influence.read() {
+        if state.pending_influence[event.player - 1] == 0 {
+            continue;
+        }
+
+        state.pending_influence[event.player - 1] -= 1;
+        let markers = &mut state.influence[event.region.index()][event.player - 1];
+        *markers += 1;
+
+        ew_influence_placed.send(InfluencePlaced {
+            player: event.player,
+            region: event.region,
+            markers: *markers,
+        });
+    }
+}
@@ -0,0 +1,278 @@
+//! Trades between players. The current player puts an offer together during
+//! `PlaceCardsOnTable`, the other player accepts or rejects it, and an
+//! accepted offer is checked again before anything changes hands.
+
+use bevy::prelude::*;
+use bevy_la_mesa::{events::AlignCardsInHand, Card, Chip, ChipArea, Hand};
+
+use super::cards::{ChipType, GameState, Kard, TransactionKind, TurnEnded, TurnPhase};
+
+/// Cash added to an offer per button press.
+pub const TRADE_CASH_STEP: i32 = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum TradeItem {
+    Cash,
+    Chip,
+    Card,
+}
+
+/// What the trade buttons do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum TradeAction {
+    /// Adds something of the current player's to the offer.
+    Offer(TradeItem),
+    /// Asks for something of the other player's.
+    Ask(TradeItem),
+    Clear,
+    Propose,
+    Accept,
+    Reject,
+}
+
+/// A trade action taken by `player`. Only the current player works on the
+/// offer, and only the player it's made to may answer it.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TradeRequest {
+    pub player: usize,
+    pub action: TradeAction,
+}
+
+/// What one player hands over in a trade.
+#[derive(Clone, Debug, Default)]
+pub struct TradeSide {
+    pub cash: i32,
+    pub chips: Vec<Entity>,
+    pub cards: Vec<Entity>,
+}
+
+impl TradeSide {
+    pub fn is_empty(&self) -> bool {
+        self.cash == 0 && self.chips.is_empty() && self.cards.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TradeOffer {
+    pub from: usize,
+    pub to: usize,
+    pub give: TradeSide,
+    pub take: TradeSide,
+}
+
+/// The offer the current player is putting together.
+#[derive(Resource, Default)]
+pub struct TradeDraft {
+    pub give: TradeSide,
+    pub take: TradeSide,
+}
+
+/// An offer waiting for the other player's answer.
+#[derive(Resource, Default)]
+pub struct PendingTrade(pub Option<TradeOffer>);
+
+/// Moves a chip to the same area on another player's side of the table.
+#[derive(Debug, Event)]
+pub struct TransferChip {
+    pub entity: Entity,
+    pub player: usize,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TradeDraft>()
+        .init_resource::<PendingTrade>()
+        .add_event::<TradeRequest>()
+        .add_event::<TransferChip>()
+        .add_systems(Update, (handle_trade_action, drop_trades_on_turn_end));
+}
+
+/// Offers only stand for the turn they were made in.
+fn drop_trades_on_turn_end(
+    mut er_turn_ended: EventReader<TurnEnded>,
+    mut draft: ResMut<TradeDraft>,
+    mut pending: ResMut<PendingTrade>,
+) {
+    for event in er_turn_ended.read() {
+        *draft = TradeDraft::default();
+        if pending
+            .0
+            .as_ref()
+            .is_some_and(|offer| offer.from == event.player)
+        {
+            pending.0 = None;
+        }
+    }
+}
+
+fn other_player(state: &GameState, player: usize) -> usize {
+    player % state.num_players() + 1
+}
+
+fn handle_trade_action(
+    mut er_trade_request: EventReader<TradeRequest>,
+    mut ew_transfer_chip: EventWriter<TransferChip>,
+    mut ew_align_cards_in_hand: EventWriter<AlignCardsInHand>,
+    mut draft: ResMut<TradeDraft>,
+    mut pending: ResMut<PendingTrade>,
+    mut state: ResMut<GameState>,
+    chips: Query<(Entity, &Transform, &ChipArea), With<Chip<ChipType>>>,
+    mut hands: Query<(Entity, &mut Hand), With<Card<Kard>>>,
+) {
+    for request in er_trade_request.read() {
+        let player = state.player;
+        let other = other_player(&state, player);
+
+        let answering = matches!(request.action, TradeAction::Accept | TradeAction::Reject);
+        let allowed = if answering {
+            pending
+                .0
+                .as_ref()
+                .is_some_and(|offer| offer.to == request.player)
+        } else {
+            request.player == player
+        };
+        if !allowed {
+            continue;
+        }
+
+        match request.action {
+            TradeAction::Offer(item) | TradeAction::Ask(item) => {
+                if state.phase != TurnPhase::PlaceCardsOnTable || pending.0.is_some() {
+                    continue;
+                }
+                let (owner, side) = match request.action {
+                    TradeAction::Offer(_) => (player, &mut draft.give),
+                    _ => (other, &mut draft.take),
+                };
+                match item {
+                    TradeItem::Cash => {
+                        if side.cash + TRADE_CASH_STEP <= state.get_balance(owner) {
+                            side.cash += TRADE_CASH_STEP;
+                        }
+                    }
+                    // Transport stock first, then production, top of the stack first.
+                    TradeItem::Chip => {
+                        let next = chips
+                            .iter()
+                            .filter(|(entity, _, area)| {
+                                area.player == owner && !side.chips.contains(entity)
+                            })
+                            .max_by(|(_, t1, a1), (_, t2, a2)| {
+                                a1.marker
+                                    .cmp(&a2.marker)
+                                    .then(t1.translation.y.partial_cmp(&t2.translation.y).unwrap())
+                            })
+                            .map(|(entity, _, _)| entity);
+                        side.chips.extend(next);
+                    }
+                    TradeItem::Card => {
+                        let next = hands
+                            .iter()
+                            .find(|(entity, hand)| {
+                                hand.player == owner && !side.cards.contains(entity)
+                            })
+                            .map(|(entity, _)| entity);
+                        side.cards.extend(next);
+                    }
+                }
+            }
+            TradeAction::Clear => {
+                *draft = TradeDraft::default();
+            }
+            TradeAction::Propose => {
+                if state.phase != TurnPhase::PlaceCardsOnTable
+                    || pending.0.is_some()
+                    || (draft.give.is_empty() && draft.take.is_empty())
+                {
+                    continue;
+                }
+                let draft = std::mem::take(&mut *draft);
+                pending.0 = Some(TradeOffer {
+                    from: player,
+                    to: other,
+                    give: draft.give,
+                    take: draft.take,
+                });
+            }
+            TradeAction::Reject => {
+                pending.0 = None;
+            }
+            TradeAction::Accept => {
+                let Some(offer) = pending.0.take() else {
+                    continue;
+                };
+
+                let side_is_valid = |owner: usize, side: &TradeSide| {
+                    side.cash >= 0
+                        && side.cash <= state.get_balance(owner)
+                        && side.chips.iter().all(|entity| {
+                            chips
+                                .get(*entity)
+                                .is_ok_and(|(_, _, area)| area.player == owner)
+                        })
+                        && side.cards.iter().all(|entity| {
+                            hands
+                                .get(*entity)
+                                .is_ok_and(|(_, hand)| hand.player == owner)
+                        })
+                };
+                // Chips keep their area, so each area has to have room for
+                // what comes in once what goes out has left.
+                let has_room = |receiver: usize, incoming: &TradeSide, outgoing: &TradeSide| {
+                    (1..=2).all(|marker| {
+                        let in_area = |side: &TradeSide| {
+                            side.chips
+                                .iter()
+                                .filter(|entity| {
+                                    chips
+                                        .get(**entity)
+                                        .is_ok_and(|(_, _, area)| area.marker == marker)
+                                })
+                                .count()
+                        };
+                        let held = chips
+                            .iter()
+                            .filter(|(_, _, area)| area.player == receiver && area.marker == marker)
+                            .count();
+                        held - in_area(outgoing) + in_area(incoming)
+                            <= state.get_storage_capacity(receiver, marker)
+                    })
+                };
+                if state.phase != TurnPhase::PlaceCardsOnTable
+                    || state.is_bankrupt(offer.from)
+                    || state.is_bankrupt(offer.to)
+                    || !side_is_valid(offer.from, &offer.give)
+                    || !side_is_valid(offer.to, &offer.take)
+                    || !has_room(offer.to, &offer.give, &offer.take)
+                    || !has_room(offer.from, &offer.take, &offer.give)
+                {
+                    continue;
+                }
+
+                for (owner, receiver, side) in [
+                    (offer.from, offer.to, &offer.give),
+                    (offer.to, offer.from, &offer.take),
+                ] {
+                    if side.cash > 0 {
+                        state.change_balance(owner, -side.cash, TransactionKind::Trade, None);
+                        state.change_balance(receiver, side.cash, TransactionKind::Trade, None);
+                    }
+                    for entity in &side.chips {
+                        ew_transfer_chip.send(TransferChip {
+                            entity: *entity,
+                            player: receiver,
+                        });
+                    }
+                    for entity in &side.cards {
+                        if let Ok((_, mut hand)) = hands.get_mut(*entity) {
+                            hand.player = receiver;
+                        }
+                    }
+                }
+
+                ew_align_cards_in_hand.send(AlignCardsInHand { player: offer.from });
+                ew_align_cards_in_hand.send(AlignCardsInHand { player: offer.to });
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Interception risk for shipments moved by transport cards. Attacks and
+//! bribes change the odds through [`Stat::InterceptionRisk`].
+
+use bevy::prelude::*;
+
+use super::{
+    cards::{CardType, GameState},
+    effects::Stat,
+};
+
+/// Base chance a Truck shipment is intercepted, in percent.
+pub const TRUCK_INTERCEPTION: i32 = 10;
+/// Base chance a Train shipment is intercepted, in percent.
+pub const TRAIN_INTERCEPTION: i32 = 5;
+/// Extra chance per chip carried; bigger shipments draw more attention.
+pub const INTERCEPTION_PER_CHIP: i32 = 1;
+
+/// Sent for each chip of an intercepted shipment.
+#[derive(Debug, Event)]
+pub struct SeizeChip {
+    pub entity: Entity,
+    /// Owner of the shipment.
+    pub player: usize,
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<SeizeChip>();
+}
+
+pub fn interception_chance(
+    state: &GameState,
+    player: usize,
+    card_type: CardType,
+    carried: usize,
+) -> f32 {
+    let base = match card_type {
+        CardType::Truck => TRUCK_INTERCEPTION,
+        CardType::Train => TRAIN_INTERCEPTION,
+        _ => 0,
+    };
+
+    let risk = state.modify_stat(
+        player,
+        Stat::InterceptionRisk,
+        base + carried as i32 * INTERCEPTION_PER_CHIP,
+    );
+    (risk as f32 / 100.0).clamp(0.0, 1.0)
+}
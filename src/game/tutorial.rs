@@ -0,0 +1,315 @@
+//! Guided first turn. Each step points at a part of the table, only lets the
+//! player do the action it teaches, and moves on once that action happens.
+
+use bevy::prelude::*;
+use bevy_la_mesa::{
+    events::{CardPress, DrawHand},
+    Hand,
+};
+
+use super::{
+    ai::AiPlayers,
+    cards::{
+        load_starting_deck, AdvancePhase, CardType, ChipType, GameState, Kard, PhaseTimer,
+        TurnPhase,
+    },
+    spawn::{level::SpawnBoard, ui::CardGameUIAction},
+};
+use crate::{screen::Screen, ui::prelude::*, GameCamera};
+
+/// Seed of the tutorial match, so interceptions and events play out the same
+/// every time.
+pub const TUTORIAL_SEED: u64 = 7;
+
+/// What moves a step on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expect {
+    DrawHand,
+    CardPress,
+    AdvancePhase,
+    /// The Continue button on the callout. The phase is held until then.
+    Continue,
+}
+
+pub struct TutorialStep {
+    pub phase: TurnPhase,
+    pub text: &'static str,
+    /// Point on the table the callout sits next to.
+    pub target: Vec3,
+    pub expects: Expect,
+}
+
+pub const TUTORIAL_STEPS: &[TutorialStep] = &[
+    TutorialStep {
+        phase: TurnPhase::Prepare,
+        text: "This is your deck. Press Draw Hand to take five cards.",
+        target: Vec3::new(7.65, 0.0, 7.0),
+        expects: Expect::DrawHand,
+    },
+    TutorialStep {
+        phase: TurnPhase::PlaceCardsOnTable,
+        text: "This is your hand. Click the Cannabis production card to pay for it and play it.",
+        target: Vec3::new(0.0, 1.5, 5.8),
+        expects: Expect::CardPress,
+    },
+    TutorialStep {
+        phase: TurnPhase::PlaceCardsOnTable,
+        text: "Played cards resolve left to right. Drag the Truck into the slot right of the Cannabis card so it loads that product first, play the Local Market too, then press Advance Phase.",
+        target: Vec3::new(-7.6 + 3.05 * 2.0, 0.0, 7.0),
+        expects: Expect::AdvancePhase,
+    },
+    TutorialStep {
+        phase: TurnPhase::DrawEventCard,
+        text: "An event card is drawn for you every turn. Some help, some hurt.",
+        target: Vec3::new(-0.6, 0.0, 2.8),
+        expects: Expect::Continue,
+    },
+    TutorialStep {
+        phase: TurnPhase::ApplyProductionCards,
+        text: "Production cards drop chips into your production area.",
+        target: Vec3::new(3.2, 0.0, 2.2),
+        expects: Expect::Continue,
+    },
+    TutorialStep {
+        phase: TurnPhase::ApplyTransportationCards,
+        text: "Transport cards move chips to the transport area. Shipments can be intercepted.",
+        target: Vec3::new(6.5, 0.0, 2.2),
+        expects: Expect::Continue,
+    },
+    TutorialStep {
+        phase: TurnPhase::ApplySalesCards,
+        text: "Sales cards sell chips that have waited in transport since an earlier turn.",
+        target: Vec3::new(6.5, 0.0, 2.2),
+        expects: Expect::Continue,
+    },
+    TutorialStep {
+        phase: TurnPhase::ApplyActionCards,
+        text: "Action cards such as Warehouse, Bribe and Attack resolve last.",
+        target: Vec3::new(-7.6 + 3.05 * 2.0, 0.0, 7.0),
+        expects: Expect::Continue,
+    },
+    TutorialStep {
+        phase: TurnPhase::End,
+        text: "That's a full turn. Your opponent plays next; the first to the target bank wins.",
+        target: Vec3::new(-7.6, 0.0, 0.0),
+        expects: Expect::Continue,
+    },
+];
+
+/// Present while the tutorial runs; removed after the last step.
+#[derive(Resource, Default)]
+pub struct Tutorial {
+    pub step: usize,
+}
+
+/// Marks a match started from the Tutorial button. Unlike [`Tutorial`] it
+/// outlasts the last step, so a restart or rematch replays the tutorial.
+#[derive(Resource)]
+pub struct TutorialMatch;
+
+impl Tutorial {
+    pub fn current(&self) -> Option<&'static TutorialStep> {
+        TUTORIAL_STEPS.get(self.step)
+    }
+
+    /// Whether a side-panel button may be used during this step.
+    pub fn allows(&self, action: &CardGameUIAction) -> bool {
+        match self.current().map(|step| step.expects) {
+            Some(Expect::DrawHand) => *action == CardGameUIAction::ButtonDrawHand,
+            Some(Expect::AdvancePhase) => *action == CardGameUIAction::ButtonAdvancePhase,
+            Some(Expect::CardPress | Expect::Continue) => false,
+            None => true,
+        }
+    }
+
+    /// Whether a card may be picked up during this step. Cards already on the
+    /// table stay put until the tutorial is over.
+    pub fn allows_card_press(&self, on_table: bool) -> bool {
+        match self.current().map(|step| step.expects) {
+            Some(Expect::CardPress | Expect::AdvancePhase) => !on_table,
+            Some(Expect::DrawHand | Expect::Continue) => false,
+            None => true,
+        }
+    }
+}
+
+/// Deck with the cards the steps talk about first, in a fixed order.
+pub fn load_tutorial_deck() -> Vec<Kard> {
+    let order = [
+        CardType::Production(ChipType::CANNABIS),
+        CardType::Truck,
+        CardType::LocalMarket,
+        CardType::Warehouse,
+        CardType::Production(ChipType::CANNABIS),
+    ];
+    let templates = load_starting_deck();
+
+    // Repeated so the first hand is the same whichever end it's drawn from.
+    order
+        .iter()
+        .cycle()
+        .take(order.len() * 3)
+        .filter_map(|card_type| {
+            templates
+                .iter()
+                .find(|template| template.card_type == *card_type)
+                .cloned()
+        })
+        .collect()
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum TutorialAction {
+    Continue,
+}
+
+#[derive(Component)]
+struct TutorialCallout;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TutorialAction>()
+        .observe(spawn_tutorial)
+        .add_systems(
+            Update,
+            (advance_tutorial, hold_tutorial_phase, update_callout)
+                .chain()
+                .run_if(in_state(Screen::Playing).and_then(resource_exists::<Tutorial>)),
+        );
+}
+
+fn spawn_tutorial(
+    _trigger: Trigger<SpawnBoard>,
+    mut commands: Commands,
+    tutorial: Option<Res<Tutorial>>,
+    mut ai_players: ResMut<AiPlayers>,
+) {
+    if tutorial.is_none() {
+        return;
+    }
+    ai_players.0 = vec![2];
+
+    commands
+        .spawn((
+            Name::new("Tutorial Callout"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(300.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                ..default()
+            },
+            TutorialCallout,
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            children.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            children.button("Continue").insert(TutorialAction::Continue);
+        });
+}
+
+fn advance_tutorial(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    mut er_draw: EventReader<DrawHand>,
+    mut er_card_press: EventReader<CardPress>,
+    mut er_advance_phase: EventReader<AdvancePhase>,
+    mut button_query: InteractionQuery<&TutorialAction>,
+    hands: Query<&Hand>,
+    callouts: Query<Entity, With<TutorialCallout>>,
+) {
+    let drew = er_draw.read().count() > 0;
+    let pressed_card = er_card_press
+        .read()
+        .any(|event| hands.get(event.card_entity).is_ok());
+    let advanced = er_advance_phase.read().count() > 0;
+    let continued = button_query.iter_mut().any(|(interaction, action)| {
+        matches!(interaction, Interaction::Pressed) && *action == TutorialAction::Continue
+    });
+
+    let Some(step) = tutorial.current() else {
+        return;
+    };
+    let done = match step.expects {
+        Expect::DrawHand => drew,
+        Expect::CardPress => pressed_card,
+        Expect::AdvancePhase => advanced,
+        Expect::Continue => continued,
+    };
+    if !done {
+        return;
+    }
+
+    tutorial.step += 1;
+    if tutorial.current().is_none() {
+        commands.remove_resource::<Tutorial>();
+        for entity in &callouts {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Keeps the phase from moving on while a Continue step is on screen.
+fn hold_tutorial_phase(
+    tutorial: Res<Tutorial>,
+    state: Res<GameState>,
+    mut phase_timer: ResMut<PhaseTimer>,
+) {
+    let Some(step) = tutorial.current() else {
+        return;
+    };
+    if step.expects == Expect::Continue && step.phase == state.phase {
+        phase_timer.0.reset();
+    }
+}
+
+fn update_callout(
+    tutorial: Res<Tutorial>,
+    mut callouts: Query<(&mut Style, &mut Visibility, &Children), With<TutorialCallout>>,
+    mut texts: Query<&mut Text>,
+    mut buttons: Query<&mut Visibility, (With<TutorialAction>, Without<TutorialCallout>)>,
+    camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+) {
+    let Some(step) = tutorial.current() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+
+    for (mut style, mut visibility, children) in callouts.iter_mut() {
+        match camera.world_to_viewport(camera_transform, step.target) {
+            Some(position) => {
+                *visibility = Visibility::Visible;
+                style.left = Val::Px(position.x);
+                style.top = Val::Px(position.y);
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = step.text.to_string();
+            }
+            if let Ok(mut visibility) = buttons.get_mut(*child) {
+                *visibility = match step.expects {
+                    Expect::Continue => Visibility::Inherited,
+                    _ => Visibility::Hidden,
+                };
+            }
+        }
+    }
+}
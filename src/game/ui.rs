@@ -1,21 +1,66 @@
-use bevy::prelude::*;
+use std::cmp::Ordering;
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::RelativeCursorPosition,
+};
 use bevy_la_mesa::{
     events::{DeckShuffle, DrawHand},
-    Chip, ChipArea,
+    Card, CardOnTable, Chip, ChipArea, Deck, DeckArea, Hand, HandArea,
 };
 
 use super::{
+    ai::AiPlayers,
     cards::{
-        AdvancePhase, ChipType, DropChip, GameOver, GameState, MoveChip, SwitchPlayer, TurnPhase,
+        AdvancePhase, CardRejected, ChipSold, ChipType, GameState, Kard, PlayBlocker, Transaction,
+        TurnPhase,
+    },
+    effects::EffectType,
+    loans::{RepayLoan, TakeLoan, CREDIT_LIMIT, LOAN_AMOUNT},
+    objectives::Objectives,
+    research::Research,
+    settings::Settings,
+    spawn::ui::{
+        self, CardGameUIAction, EffectBadges, HandCardBadge, LedgerList, PayoutNumber, PhaseStep,
+        TableAnchor, Toast,
     },
-    spawn::ui::{self, CardGameUIAction},
+    territory::{PlaceInfluence, Region, TerritorySettings},
+    trade::{PendingTrade, TradeAction, TradeDraft, TradeRequest, TradeSide},
+    tutorial::Tutorial,
 };
-use crate::ui::prelude::InteractionQuery;
+use crate::{
+    screen::Screen,
+    ui::{palette::*, prelude::InteractionQuery, widgets::Widgets},
+    GameCamera,
+};
+
+/// How long a toast stays on screen.
+const TOAST_SECONDS: f32 = 2.5;
+/// How long a payout number rises before it's gone.
+const PAYOUT_SECONDS: f32 = 1.2;
+/// How far a payout number rises, in pixels.
+const PAYOUT_RISE: f32 = 60.0;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (handle_gameplay_action, handle_labels, handle_game_over),
+        (
+            handle_gameplay_action,
+            handle_labels,
+            update_phase_tracker,
+            update_player_panels,
+            anchor_table_actions,
+            update_hand_badges,
+            show_rejection_toast,
+            expire_toasts,
+            show_payout_numbers,
+            float_payout_numbers,
+            update_effect_badges,
+            update_ledger_list,
+            scroll_ledger,
+            update_trade_offer,
+        ),
     );
 }
 
@@ -24,77 +69,85 @@ fn handle_gameplay_action(
     mut ew_shuffle: EventWriter<DeckShuffle>,
     mut ew_draw: EventWriter<DrawHand>,
     mut ew_advance_phase: EventWriter<AdvancePhase>,
-    mut ew_drop_chip: EventWriter<DropChip>,
-    mut ew_move_chip: EventWriter<MoveChip>,
-    mut ew_switch_player: EventWriter<SwitchPlayer>,
-    chips: Query<(Entity, &Transform, &Chip<ChipType>, &ChipArea)>,
+    mut ew_take_loan: EventWriter<TakeLoan>,
+    mut ew_repay_loan: EventWriter<RepayLoan>,
+    mut ew_research: EventWriter<Research>,
+    mut ew_place_influence: EventWriter<PlaceInfluence>,
+    mut ew_trade_request: EventWriter<TradeRequest>,
+    tutorial: Option<Res<Tutorial>>,
     state: Res<GameState>,
+    ai_players: Res<AiPlayers>,
+    pending_trade: Res<PendingTrade>,
 ) {
     for (interaction, action) in &mut button_query {
+        if tutorial
+            .as_ref()
+            .is_some_and(|tutorial| !tutorial.allows(action))
+        {
+            continue;
+        }
         if matches!(interaction, Interaction::Pressed) {
             match action {
                 CardGameUIAction::ButtonShuffleDeck => {
-                    ew_shuffle.send(DeckShuffle { deck_marker: 1 });
+                    ew_shuffle.send(DeckShuffle {
+                        deck_marker: state.deck_marker(state.player),
+                    });
                     ew_shuffle.send(DeckShuffle { deck_marker: 2 });
                 }
                 CardGameUIAction::ButtonDrawHand => {
                     let event = DrawHand {
-                        deck_marker: 1,
+                        deck_marker: state.deck_marker(state.player),
                         num_cards: 5,
                         player: state.player,
                     };
                     ew_draw.send(event);
                     ew_advance_phase.send(AdvancePhase);
                 }
-                CardGameUIAction::ButtonDropChip => {
-                    let event = DropChip {
-                        chip_type: ChipType::Cannabis,
-                        area: 1,
+                CardGameUIAction::ButtonAdvancePhase => {
+                    ew_advance_phase.send(AdvancePhase);
+                }
+                CardGameUIAction::ButtonTakeLoan => {
+                    ew_take_loan.send(TakeLoan {
                         player: state.player,
-                    };
-                    ew_drop_chip.send(event);
+                        amount: LOAN_AMOUNT,
+                    });
                 }
-                CardGameUIAction::ButtonMoveChip => {
-                    // 1. find highest chip in area
-                    let chips_in_area = chips
-                        .iter()
-                        .filter(|(_, _, chip, area)| {
-                            chip.data == ChipType::Cannabis && area.marker == 1
-                        })
-                        .count();
-
-                    if chips_in_area == 0 {
-                        continue;
-                    }
-
-                    let chip_entity = chips
-                        .iter()
-                        .filter(|(_, _, chip, area)| {
-                            chip.data == ChipType::Cannabis && area.marker == 1
-                        })
-                        .max_by_key(|(_, transform, _, _)| {
-                            (transform.translation.z * 100.0) as usize
-                        })
-                        .map(|(entity, _, _, _)| entity)
-                        .unwrap();
-
-                    let event = MoveChip {
-                        entity: chip_entity,
-                        area: 2,
+                CardGameUIAction::ButtonRepayLoan => {
+                    ew_repay_loan.send(RepayLoan {
                         player: state.player,
-                    };
-                    ew_move_chip.send(event);
+                        amount: LOAN_AMOUNT,
+                    });
                 }
-                CardGameUIAction::ButtonAdvancePhase => {
-                    ew_advance_phase.send(AdvancePhase);
+                CardGameUIAction::ButtonResearch(technology) => {
+                    ew_research.send(Research {
+                        player: state.player,
+                        technology: *technology,
+                    });
                 }
-                CardGameUIAction::ButtonSwitchPlayer => {
-                    ew_switch_player.send(SwitchPlayer {
-                        player: match state.player {
-                            1 => 2,
-                            2 => 1,
-                            _ => 1,
-                        },
+                CardGameUIAction::ButtonInfluence(region) => {
+                    ew_place_influence.send(PlaceInfluence {
+                        player: state.player,
+                        region: *region,
+                    });
+                }
+                // Offers are answered from the other player's seat, unless
+                // the computer plays it.
+                CardGameUIAction::ButtonTrade(
+                    action @ (TradeAction::Accept | TradeAction::Reject),
+                ) => {
+                    if let Some(offer) = &pending_trade.0 {
+                        if !ai_players.0.contains(&offer.to) {
+                            ew_trade_request.send(TradeRequest {
+                                player: offer.to,
+                                action: *action,
+                            });
+                        }
+                    }
+                }
+                CardGameUIAction::ButtonTrade(action) => {
+                    ew_trade_request.send(TradeRequest {
+                        player: state.player,
+                        action: *action,
                     });
                 }
                 _ => {}
@@ -106,7 +159,14 @@ fn handle_gameplay_action(
 fn handle_labels(
     mut label_query: Query<(Entity, &mut Visibility, &CardGameUIAction)>,
     mut text_query: Query<(&Parent, &mut Text)>,
+    chips: Query<&ChipArea, With<Chip<ChipType>>>,
+    decks: Query<&Deck, With<Card<Kard>>>,
     state: Res<GameState>,
+    territory_settings: Res<TerritorySettings>,
+    trade_draft: Res<TradeDraft>,
+    pending_trade: Res<PendingTrade>,
+    objectives: Res<Objectives>,
+    ai_players: Res<AiPlayers>,
 ) {
     for (entity, mut visibility, ui_element) in &mut label_query {
         for (parent, mut text) in text_query.iter_mut() {
@@ -115,21 +175,18 @@ fn handle_labels(
             }
             match ui_element {
                 CardGameUIAction::LabelTurnNumber => {
-                    text.sections[0].value = format!("Turn number: {}", state.turn_number);
-                }
-                CardGameUIAction::LabelTurnPhase => {
-                    text.sections[0].value = format!("Turn phase: {:?}", state.phase);
+                    text.sections[0].value = format!("Turn {}", state.turn_number);
                 }
                 CardGameUIAction::ButtonShuffleDeck => {
                     if state.phase == TurnPhase::Prepare {
-                        *visibility = Visibility::Visible;
+                        *visibility = Visibility::Inherited;
                     } else {
                         *visibility = Visibility::Hidden;
                     }
                 }
                 CardGameUIAction::ButtonDrawHand => {
                     if state.phase == TurnPhase::Prepare {
-                        *visibility = Visibility::Visible;
+                        *visibility = Visibility::Inherited;
                     } else {
                         *visibility = Visibility::Hidden;
                     }
@@ -153,19 +210,157 @@ fn handle_labels(
                         TurnPhase::ApplyActionCards => "Applying Action Cards".to_string(),
                     };
                 }
-                CardGameUIAction::ButtonDropChip => {}
-                CardGameUIAction::ButtonMoveChip => {}
-                CardGameUIAction::ButtonAdvancePhase => {}
-                CardGameUIAction::LabelPlayerNumber => {
-                    text.sections[0].value = format!("Player number: {}", state.player)
+                CardGameUIAction::LabelObjectives => {
+                    if objectives.objectives.is_empty() {
+                        *visibility = Visibility::Hidden;
+                        continue;
+                    }
+                    *visibility = Visibility::Visible;
+                    let lines: Vec<String> = objectives
+                        .objectives
+                        .iter()
+                        .map(|tracker| tracker.describe())
+                        .collect();
+                    text.sections[0].value = lines.join("\n");
                 }
-                CardGameUIAction::ButtonSwitchPlayer => {}
-                CardGameUIAction::LabelBank => {
-                    text.sections[0].value = format!("Bank: ${}", state.get_balance(state.player));
+                CardGameUIAction::ButtonAdvancePhase => {
+                    // The other phases move on by themselves.
+                    if state.phase == TurnPhase::PlaceCardsOnTable {
+                        *visibility = Visibility::Inherited;
+                    } else {
+                        *visibility = Visibility::Hidden;
+                    }
                 }
-                CardGameUIAction::LabelEffects => {
-                    text.sections[0].value =
-                        format!("Effects: {:?}", state.get_effects(state.player));
+                CardGameUIAction::LabelPlayerName(player) => {
+                    let mut name = format!("Player {}", player);
+                    if ai_players.0.contains(player) {
+                        name.push_str(" (AI)");
+                    }
+                    if state.is_bankrupt(*player) {
+                        name.push_str(" - BANKRUPT");
+                    }
+                    text.sections[0].value = name;
+                }
+                CardGameUIAction::LabelPlayerBank(player) => {
+                    text.sections[0].value = format!("Bank: ${}", state.get_balance(*player));
+                }
+                CardGameUIAction::LabelPlayerChips(player) => {
+                    let chips_in_area = |marker: usize| {
+                        chips
+                            .iter()
+                            .filter(|area| area.player == *player && area.marker == marker)
+                            .count()
+                    };
+                    text.sections[0].value = format!(
+                        "Production {}/{} | Transport {}/{}",
+                        chips_in_area(1),
+                        state.get_storage_capacity(*player, 1),
+                        chips_in_area(2),
+                        state.get_storage_capacity(*player, 2)
+                    );
+                }
+                CardGameUIAction::LabelDeck => {
+                    let cards_in_deck =
+                        |marker: usize| decks.iter().filter(|deck| deck.marker == marker).count();
+                    text.sections[0].value = format!(
+                        "Deck: {} | Discard: {}",
+                        cards_in_deck(state.deck_marker(state.player)),
+                        cards_in_deck(state.discard_marker(state.player))
+                    );
+                }
+                CardGameUIAction::ButtonTakeLoan | CardGameUIAction::ButtonRepayLoan => {
+                    if matches!(
+                        state.phase,
+                        TurnPhase::Prepare | TurnPhase::PlaceCardsOnTable
+                    ) {
+                        *visibility = Visibility::Visible;
+                    } else {
+                        *visibility = Visibility::Hidden;
+                    }
+                }
+                CardGameUIAction::ButtonResearch(technology) => {
+                    if matches!(
+                        state.phase,
+                        TurnPhase::Prepare | TurnPhase::PlaceCardsOnTable
+                    ) && state.can_research(state.player, *technology)
+                    {
+                        *visibility = Visibility::Visible;
+                    } else {
+                        *visibility = Visibility::Hidden;
+                    }
+                }
+                CardGameUIAction::LabelTerritory => {
+                    if !territory_settings.enabled {
+                        *visibility = Visibility::Hidden;
+                        continue;
+                    }
+                    let mut lines = vec![format!(
+                        "Territory ({} to place)",
+                        state.pending_influence[state.player - 1]
+                    )];
+                    for region in Region::ALL {
+                        let controller = match state.region_controller(region) {
+                            Some(player) => format!("Player {}", player),
+                            None => "uncontrolled".to_string(),
+                        };
+                        lines.push(format!(
+                            "{}: {} {:?}",
+                            region.definition().name,
+                            controller,
+                            state.influence[region.index()]
+                        ));
+                    }
+                    text.sections[0].value = lines.join("\n");
+                }
+                CardGameUIAction::ButtonInfluence(_) => {
+                    if state.pending_influence[state.player - 1] > 0 {
+                        *visibility = Visibility::Visible;
+                    } else {
+                        *visibility = Visibility::Hidden;
+                    }
+                }
+                CardGameUIAction::LabelTrade => {
+                    text.sections[0].value = format!(
+                        "Trade\nYou give: {}\nYou get: {}",
+                        format_trade_side(&trade_draft.give),
+                        format_trade_side(&trade_draft.take)
+                    );
+                }
+                CardGameUIAction::ButtonTrade(TradeAction::Accept | TradeAction::Reject) => {}
+                CardGameUIAction::ButtonTrade(_) => {
+                    if state.phase == TurnPhase::PlaceCardsOnTable && pending_trade.0.is_none() {
+                        *visibility = Visibility::Visible;
+                    } else {
+                        *visibility = Visibility::Hidden;
+                    }
+                }
+                CardGameUIAction::LabelTradeOffer => {
+                    if let Some(offer) = &pending_trade.0 {
+                        text.sections[0].value = format!(
+                            "Player {}, player {} offers a trade\nYou get: {}\nYou give: {}",
+                            offer.to,
+                            offer.from,
+                            format_trade_side(&offer.give),
+                            format_trade_side(&offer.take)
+                        );
+                    }
+                }
+                CardGameUIAction::LabelLedger => {
+                    let mut lines = vec![format!(
+                        "Debt: ${} / ${}",
+                        state.get_debt(state.player),
+                        CREDIT_LIMIT
+                    )];
+                    for loan in state.get_loans(state.player) {
+                        lines.push(format!(
+                            "Loan (turn {}): ${} of ${} at {}%",
+                            loan.turn_number, loan.outstanding, loan.principal, loan.interest_rate
+                        ));
+                    }
+                    if state.is_bankrupt(state.player) {
+                        lines.push("BANKRUPT".to_string());
+                    }
+                    text.sections[0].value = lines.join("\n");
                 }
                 _ => {}
             }
@@ -173,25 +368,508 @@ fn handle_labels(
     }
 }
 
-pub fn handle_game_over(
-    mut query: Query<(&CardGameUIAction, &mut Text, &mut Visibility), With<Text>>,
-    mut game_over_container_visibility: Query<(&CardGameUIAction, &mut Visibility), Without<Text>>,
-    mut er_game_over: EventReader<GameOver>,
+fn update_effect_badges(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut EffectBadges)>,
+    state: Res<GameState>,
 ) {
-    for event in er_game_over.read() {
-        for (ui_action, mut text, mut visibility) in query.iter_mut() {
-            if ui_action != &CardGameUIAction::LabelGameOver {
-                continue;
+    for (entity, mut badges) in &mut query {
+        let effects: Vec<(EffectType, usize)> = state
+            .get_effects(badges.player)
+            .iter()
+            .map(|effect| {
+                (
+                    effect.effect_type,
+                    effect.remaining_turns(state.turn_number),
+                )
+            })
+            .collect();
+        if badges.shown == effects {
+            continue;
+        }
+        badges.shown = effects.clone();
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|children| {
+                for (effect_type, remaining) in &effects {
+                    children
+                        .spawn((
+                            Name::new("Effect Badge"),
+                            NodeBundle {
+                                style: Style {
+                                    padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                    ..default()
+                                },
+                                background_color: BackgroundColor(effect_badge_color(*effect_type)),
+                                border_radius: BorderRadius::all(Val::Px(6.0)),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|children| {
+                            children.spawn(TextBundle::from_section(
+                                format!(
+                                    "{} · {} {}",
+                                    effect_type.name(),
+                                    remaining,
+                                    if *remaining == 1 { "turn" } else { "turns" }
+                                ),
+                                TextStyle {
+                                    font_size: 14.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            ));
+                        });
+                }
+            });
+    }
+}
+
+/// Highlights the current phase; phases already played this turn are dimmed.
+fn update_phase_tracker(
+    mut steps: Query<(&PhaseStep, &mut BackgroundColor)>,
+    state: Res<GameState>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let current = TurnPhase::ALL
+        .iter()
+        .position(|phase| *phase == state.phase)
+        .unwrap_or(0);
+    for (step, mut background) in &mut steps {
+        let index = TurnPhase::ALL
+            .iter()
+            .position(|phase| *phase == step.0)
+            .unwrap_or(0);
+        background.0 = match index.cmp(&current) {
+            Ordering::Less => HUD_PHASE_DONE,
+            Ordering::Equal => HUD_PHASE_CURRENT,
+            Ordering::Greater => HUD_PHASE_UPCOMING,
+        };
+    }
+}
+
+/// Outlines the panel of the player whose turn it is.
+fn update_player_panels(
+    mut panels: Query<(&CardGameUIAction, &mut BorderColor)>,
+    state: Res<GameState>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    for (ui_action, mut border) in &mut panels {
+        let CardGameUIAction::ContainerPlayerPanel(player) = ui_action else {
+            continue;
+        };
+        border.0 = if *player == state.player {
+            HUD_ACTIVE_BORDER
+        } else {
+            Color::NONE
+        };
+    }
+}
+
+/// Keeps the deck and hand actions next to the current player's deck and
+/// hand, and out of the way while the computer plays.
+fn anchor_table_actions(
+    mut anchored: Query<(&TableAnchor, &mut Style, &mut Visibility)>,
+    decks: Query<(&DeckArea, &GlobalTransform)>,
+    hands: Query<(&HandArea, &GlobalTransform)>,
+    camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    state: Res<GameState>,
+    ai_players: Res<AiPlayers>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let deck = decks
+        .iter()
+        .find(|(deck, _)| deck.marker == state.deck_marker(state.player))
+        .map(|(_, transform)| transform.translation());
+    let hand = hands
+        .iter()
+        .find(|(hand, _)| hand.player == state.player)
+        .map(|(_, transform)| transform.translation());
+
+    for (anchor, mut style, mut visibility) in &mut anchored {
+        let target = match anchor {
+            TableAnchor::Deck => deck,
+            TableAnchor::Hand => hand,
+        };
+        let position = target.and_then(|target| camera.world_to_viewport(camera_transform, target));
+        match position {
+            Some(position) if !ai_players.0.contains(&state.player) => {
+                *visibility = Visibility::Visible;
+                style.left = Val::Px(position.x);
+                style.top = Val::Px(position.y);
             }
-            *visibility = Visibility::Visible;
-            text.sections[0].value = format!("Game Over; {} player won!", event.player_won);
+            _ => *visibility = Visibility::Hidden,
         }
+    }
+}
 
-        for (ui_action, mut visibility) in game_over_container_visibility.iter_mut() {
-            if ui_action != &CardGameUIAction::ContainerGameOver {
+/// Marks each of the current player's hand cards as playable, unaffordable
+/// or blocked, using the same check as the rules.
+fn update_hand_badges(
+    mut commands: Commands,
+    mut badges: Query<(
+        Entity,
+        &HandCardBadge,
+        &mut Style,
+        &mut BackgroundColor,
+        &mut Visibility,
+        &Children,
+    )>,
+    mut texts: Query<&mut Text>,
+    cards_in_hand: Query<(Entity, &Card<Kard>, &Hand, &GlobalTransform)>,
+    cards_on_table: Query<&CardOnTable>,
+    camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    state: Res<GameState>,
+    ai_players: Res<AiPlayers>,
+    settings: Res<Settings>,
+) {
+    let human = !ai_players.0.contains(&state.player);
+    let hand_cards: Vec<_> = cards_in_hand
+        .iter()
+        .filter(|(_, _, hand, _)| human && hand.player == state.player)
+        .collect();
+
+    for (entity, badge, ..) in &badges {
+        if !hand_cards.iter().any(|(card, ..)| *card == badge.card) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for (card, ..) in &hand_cards {
+        if badges.iter().any(|(_, badge, ..)| badge.card == *card) {
+            continue;
+        }
+        commands
+            .spawn((
+                Name::new("Hand Card Badge"),
+                NodeBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    border_radius: BorderRadius::all(Val::Px(6.0)),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                HandCardBadge { card: *card },
+                StateScoped(Screen::Playing),
+            ))
+            .with_children(|children| {
+                children.spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            });
+    }
+
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let occupied: Vec<usize> = cards_on_table
+        .iter()
+        .filter(|card_on_table| card_on_table.player == state.player)
+        .map(|card_on_table| card_on_table.marker)
+        .collect();
+
+    for (_, badge, mut style, mut background, mut visibility, children) in &mut badges {
+        let Some((_, kard, hand, transform)) =
+            hand_cards.iter().find(|(card, ..)| *card == badge.card)
+        else {
+            continue;
+        };
+        let look = match state.check_card_play(hand.player, &kard.data, &occupied, None) {
+            Ok(_) => Some(("Playable".to_string(), hud_positive(settings.colour_blind))),
+            Err(PlayBlocker::CannotAfford(price)) => Some((
+                format!("${} - can't afford", price),
+                hud_negative(settings.colour_blind),
+            )),
+            Err(PlayBlocker::NoFreeSlot | PlayBlocker::SlotTaken) => {
+                Some(("No free slot".to_string(), HUD_CARD_BLOCKED))
+            }
+            Err(PlayBlocker::Bankrupt) => Some(("Bankrupt".to_string(), HUD_CARD_BLOCKED)),
+            // Nothing to mark outside the play phase.
+            Err(PlayBlocker::WrongPhase | PlayBlocker::NotYourTurn) => None,
+        };
+        let position = camera.world_to_viewport(camera_transform, transform.translation());
+        let (Some((text, color)), Some(position)) = (look, position) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        style.left = Val::Px(position.x);
+        style.top = Val::Px(position.y);
+        background.0 = color;
+        for child in children.iter() {
+            if let Ok(mut value) = texts.get_mut(*child) {
+                value.sections[0].value.clone_from(&text);
+            }
+        }
+    }
+}
+
+/// Tells the player why a card press was turned down.
+fn show_rejection_toast(
+    mut commands: Commands,
+    mut er_card_rejected: EventReader<CardRejected>,
+    toasts: Query<Entity, With<Toast>>,
+) {
+    let Some(event) = er_card_rejected.read().last() else {
+        return;
+    };
+    for entity in &toasts {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands
+        .spawn((
+            Name::new("Toast"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    bottom: Val::Percent(35.0),
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+            Toast(Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)),
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(HUD_PANEL_BACKGROUND),
+                    border_radius: BorderRadius::all(Val::Px(6.0)),
+                    ..default()
+                })
+                .with_children(|children| {
+                    children.spawn(TextBundle::from_section(
+                        event.blocker.message(),
+                        TextStyle {
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+fn expire_toasts(mut commands: Commands, mut toasts: Query<(Entity, &mut Toast)>, time: Res<Time>) {
+    for (entity, mut toast) in &mut toasts {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// One rising "+$" number per player and sale, where the chips left from.
+fn show_payout_numbers(
+    mut commands: Commands,
+    mut er_chip_sold: EventReader<ChipSold>,
+    chips: Query<&GlobalTransform>,
+    camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    settings: Res<Settings>,
+) {
+    let mut payouts: Vec<(usize, i32, Option<Vec3>)> = vec![];
+    for event in er_chip_sold.read() {
+        let position = chips.get(event.entity).ok().map(|chip| chip.translation());
+        match payouts
+            .iter_mut()
+            .find(|(player, ..)| *player == event.player)
+        {
+            Some((_, total, _)) => *total += event.price,
+            None => payouts.push((event.player, event.price, position)),
+        }
+    }
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+
+    for (_, total, position) in payouts {
+        let Some(position) =
+            position.and_then(|position| camera.world_to_viewport(camera_transform, position))
+        else {
+            continue;
+        };
+        commands.spawn((
+            Name::new("Payout Number"),
+            TextBundle::from_section(
+                format!("+${}", total),
+                TextStyle {
+                    font_size: 32.0,
+                    color: hud_positive(settings.colour_blind),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                left: Val::Px(position.x),
+                top: Val::Px(position.y),
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+            PayoutNumber(Timer::from_seconds(PAYOUT_SECONDS, TimerMode::Once)),
+            StateScoped(Screen::Playing),
+        ));
+    }
+}
+
+fn float_payout_numbers(
+    mut commands: Commands,
+    mut numbers: Query<(Entity, &mut PayoutNumber, &mut Style, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut number, mut style, mut text) in &mut numbers {
+        number.0.tick(time.delta());
+        if number.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        if let Val::Px(top) = style.top {
+            style.top = Val::Px(top - PAYOUT_RISE * time.delta_seconds() / PAYOUT_SECONDS);
+        }
+        let alpha = 1.0 - number.0.fraction();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
+
+fn effect_badge_color(effect_type: EffectType) -> Color {
+    match effect_type {
+        EffectType::Drought => Color::srgb(0.6, 0.4, 0.1),
+        EffectType::Attack => Color::srgb(0.7, 0.1, 0.1),
+        EffectType::Bribe => Color::srgb(0.1, 0.3, 0.7),
+        EffectType::BigDeal => Color::srgb(0.1, 0.55, 0.2),
+    }
+}
+
+fn update_ledger_list(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut LedgerList, &mut Style)>,
+    state: Res<GameState>,
+) {
+    for (entity, mut list, mut style) in &mut query {
+        let transactions = state.get_transactions(state.player);
+        if list.player == state.player && list.len == transactions.len() {
+            continue;
+        }
+
+        list.player = state.player;
+        list.len = transactions.len();
+        list.position = 0.0;
+        style.top = Val::Px(0.0);
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|children| {
+                // Newest first, so the latest movement is always in view.
+                for transaction in transactions.iter().rev() {
+                    children.label(format_transaction(transaction));
+                }
+            });
+    }
+}
+
+fn scroll_ledger(
+    mut er_mouse_wheel: EventReader<MouseWheel>,
+    containers: Query<(&RelativeCursorPosition, &Node), With<CardGameUIAction>>,
+    mut lists: Query<(&mut LedgerList, &mut Style, &Node, &Parent)>,
+) {
+    for event in er_mouse_wheel.read() {
+        for (mut list, mut style, list_node, parent) in &mut lists {
+            let Ok((cursor, container_node)) = containers.get(parent.get()) else {
+                continue;
+            };
+            if !cursor.mouse_over() {
                 continue;
             }
-            *visibility = Visibility::Visible;
+
+            let max_scroll = (list_node.size().y - container_node.size().y).max(0.0);
+            let dy = match event.unit {
+                MouseScrollUnit::Line => event.y * 20.0,
+                MouseScrollUnit::Pixel => event.y,
+            };
+            list.position = (list.position + dy).clamp(-max_scroll, 0.0);
+            style.top = Val::Px(list.position);
+        }
+    }
+}
+
+fn format_transaction(transaction: &Transaction) -> String {
+    let source = match transaction.card {
+        Some(card_type) => format!(" ({})", card_type.name()),
+        None => String::new(),
+    };
+    format!(
+        "T{} {:?}: {}{}",
+        transaction.turn_number,
+        transaction.kind,
+        format_amount(transaction.amount),
+        source
+    )
+}
+
+fn format_trade_side(side: &TradeSide) -> String {
+    if side.is_empty() {
+        return "nothing".to_string();
+    }
+    let mut parts = vec![];
+    if side.cash > 0 {
+        parts.push(format!("${}", side.cash));
+    }
+    if !side.chips.is_empty() {
+        parts.push(format!("{} chip(s)", side.chips.len()));
+    }
+    if !side.cards.is_empty() {
+        parts.push(format!("{} card(s)", side.cards.len()));
+    }
+    parts.join(", ")
+}
+
+/// Shows the offer modal while a trade is waiting for an answer.
+fn update_trade_offer(
+    mut query: Query<(&CardGameUIAction, &mut Visibility)>,
+    pending_trade: Res<PendingTrade>,
+) {
+    if !pending_trade.is_changed() {
+        return;
+    }
+    for (ui_action, mut visibility) in query.iter_mut() {
+        if ui_action != &CardGameUIAction::ContainerTradeOffer {
+            continue;
         }
+        *visibility = match pending_trade.0 {
+            Some(_) => Visibility::Visible,
+            None => Visibility::Hidden,
+        };
+    }
+}
+
+fn format_amount(amount: i32) -> String {
+    if amount < 0 {
+        format!("-${}", -amount)
+    } else {
+        format!("+${}", amount)
     }
 }
@@ -0,0 +1,53 @@
+//! Storage limits for chip areas and spoilage of unsold stock.
+
+use bevy::prelude::*;
+use bevy_la_mesa::{Chip, ChipArea};
+
+use super::{
+    cards::{ChipType, DiscardChip, GameState, TurnEnded},
+    effects::Stat,
+};
+
+/// Chips a player can keep in production (`ChipArea { marker: 1 }`).
+pub const PRODUCTION_CAPACITY: usize = 20;
+/// Chips a player can keep in transport (`ChipArea { marker: 2 }`).
+pub const TRANSPORT_CAPACITY: usize = 30;
+/// Extra room in both areas per Warehouse card played.
+pub const WAREHOUSE_UPGRADE: usize = 10;
+/// Turns a chip can wait in production before it spoils.
+pub const SPOILAGE_TURNS: usize = 4;
+/// Turns a chip can wait in transport before it's seized.
+pub const SEIZURE_TURNS: usize = 3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, spoil_chips);
+}
+
+fn spoil_chips(
+    mut er_turn_ended: EventReader<TurnEnded>,
+    state: Res<GameState>,
+    chips: Query<(Entity, &Chip<ChipType>, &ChipArea)>,
+    mut ew_discard_chip: EventWriter<DiscardChip>,
+) {
+    for event in er_turn_ended.read() {
+        for (entity, chip, area) in chips.iter().filter(|(_, _, a)| a.player == event.player) {
+            if chip_expired(&state, chip, area) {
+                ew_discard_chip.send(DiscardChip { entity });
+            }
+        }
+    }
+}
+
+/// Whether a chip has spoiled in production or been seized in transport.
+pub fn chip_expired(state: &GameState, chip: &Chip<ChipType>, area: &ChipArea) -> bool {
+    match area.marker {
+        1 => chip.turn_activation_1 + SPOILAGE_TURNS <= state.turn_number,
+        2 => {
+            let seizure_turns =
+                state.modify_stat(area.player, Stat::SeizureTurns, SEIZURE_TURNS as i32) as usize;
+            chip.turn_activation_2 != 0
+                && chip.turn_activation_2 + seizure_turns <= state.turn_number
+        }
+        _ => false,
+    }
+}
@@ -13,7 +13,10 @@ use bevy::{
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_la_mesa::{LaMesaPlugin, LaMesaPluginSettings};
 use bevy_obj::ObjPlugin;
-use game::cards::{ChipType, Kard};
+use game::{
+    cards::{ChipType, Kard},
+    settings::BASE_VOLUME,
+};
 
 pub struct AppPlugin;
 
@@ -48,7 +51,7 @@ impl Plugin for AppPlugin {
                 })
                 .set(AudioPlugin {
                     global_volume: GlobalVolume {
-                        volume: Volume::new(0.3),
+                        volume: Volume::new(BASE_VOLUME),
                     },
                     ..default()
                 }),
@@ -70,7 +73,7 @@ impl Plugin for AppPlugin {
 
         #[cfg(feature = "dev")]
         app.add_plugins(
-            WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::Escape)),
+            WorldInspectorPlugin::default().run_if(input_toggle_active(false, KeyCode::F1)),
         );
     }
 }
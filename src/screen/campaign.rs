@@ -0,0 +1,77 @@
+//! The campaign map, listing the scenarios unlocked so far.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::campaign::{ActiveScenario, Campaign, CampaignProgress},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Campaign), enter_campaign);
+
+    app.add_systems(
+        Update,
+        handle_campaign_action.run_if(in_state(Screen::Campaign)),
+    );
+    app.register_type::<CampaignAction>();
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum CampaignAction {
+    Play(usize),
+    Back,
+}
+
+fn enter_campaign(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Campaign))
+        .with_children(|children| {
+            children.header("Campaign");
+            for (index, scenario) in campaign.scenarios.iter().enumerate() {
+                if index > progress.completed {
+                    children.label(format!("{}. {} (locked)", index + 1, scenario.name));
+                    continue;
+                }
+
+                let status = if index < progress.completed {
+                    " (won)"
+                } else {
+                    ""
+                };
+                children
+                    .button(format!("{}. {}{}", index + 1, scenario.name, status))
+                    .insert(CampaignAction::Play(index));
+                children.label(format!(
+                    "{} Reach ${} by turn {}.",
+                    scenario.description, scenario.target_bank, scenario.turn_limit
+                ));
+            }
+            children.button("Back").insert(CampaignAction::Back);
+        });
+}
+
+fn handle_campaign_action(
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&CampaignAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                CampaignAction::Play(index) => {
+                    commands.insert_resource(ActiveScenario(*index));
+                    next_screen.set(Screen::Playing);
+                }
+                CampaignAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! The drafting screen for the draft variant, shown before the board.
+
+use bevy::prelude::*;
+use bevy_la_mesa::LaMesaPluginSettings;
+
+use super::Screen;
+use crate::{
+    game::{
+        cards::{load_playing_deck, GameRng},
+        draft::{Draft, DraftedDecks},
+        territory::{load_influence_cards, TerritorySettings},
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Draft), enter_draft);
+    app.add_systems(OnExit(Screen::Draft), exit_draft);
+
+    app.add_systems(
+        Update,
+        (handle_draft_action, update_draft_pack)
+            .chain()
+            .run_if(in_state(Screen::Draft)),
+    );
+    app.register_type::<DraftAction>();
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum DraftAction {
+    Pick(usize),
+    Back,
+}
+
+/// Holds the buttons for the cards in the current pack.
+#[derive(Component)]
+struct DraftPack;
+
+/// Says whose pick it is.
+#[derive(Component)]
+struct DraftStatus;
+
+fn enter_draft(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    plugin_settings: Res<LaMesaPluginSettings>,
+    territory_settings: Res<TerritorySettings>,
+) {
+    let mut pool = load_playing_deck(plugin_settings.num_players);
+    if territory_settings.enabled {
+        pool.extend(load_influence_cards(plugin_settings.num_players));
+    }
+    commands.insert_resource(Draft::new(pool, plugin_settings.num_players, &mut rng));
+
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Draft))
+        .with_children(|children| {
+            children.header("Draft");
+            children.label("").insert(DraftStatus);
+            children.spawn((
+                Name::new("Pack"),
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(80.0),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        column_gap: Val::Px(10.0),
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                DraftPack,
+            ));
+            children.button("Back").insert(DraftAction::Back);
+        });
+}
+
+fn exit_draft(mut commands: Commands) {
+    commands.remove_resource::<Draft>();
+}
+
+fn handle_draft_action(
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&DraftAction>,
+    mut draft: ResMut<Draft>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                DraftAction::Pick(index) => {
+                    draft.pick(*index);
+                    if draft.is_complete() {
+                        commands.insert_resource(DraftedDecks(draft.picks.clone()));
+                        next_screen.set(Screen::Playing);
+                    }
+                }
+                DraftAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
+
+fn update_draft_pack(
+    mut commands: Commands,
+    draft: Res<Draft>,
+    pack_query: Query<Entity, With<DraftPack>>,
+    status_query: Query<&Children, With<DraftStatus>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !draft.is_changed() {
+        return;
+    }
+
+    for children in &status_query {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = format!(
+                    "Player {} picks ({} cards in deck)",
+                    draft.player,
+                    draft.picks[draft.player - 1].len()
+                );
+            }
+        }
+    }
+
+    for entity in &pack_query {
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|children| {
+                for (index, card) in draft.packs[draft.player - 1].iter().enumerate() {
+                    children
+                        .button(format!("{} (${})", card.card_type.name(), card.price))
+                        .insert(DraftAction::Pick(index));
+                }
+            });
+    }
+}
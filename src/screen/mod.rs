@@ -1,8 +1,12 @@
 //! The game's main screen states and transitions between them.
 
+mod campaign;
 mod credits;
+mod draft;
 mod loading;
 mod playing;
+mod results;
+mod settings;
 mod splash;
 mod title;
 
@@ -11,13 +15,21 @@ use bevy::prelude::*;
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
     app.enable_state_scoped_entities::<Screen>();
+    app.add_sub_state::<Pause>();
+    app.enable_state_scoped_entities::<Pause>();
+    app.add_sub_state::<ResultsView>();
+    app.enable_state_scoped_entities::<ResultsView>();
 
     app.add_plugins((
         splash::plugin,
         loading::plugin,
         title::plugin,
         credits::plugin,
+        settings::plugin,
+        campaign::plugin,
+        draft::plugin,
         playing::plugin,
+        results::plugin,
     ));
 }
 
@@ -29,5 +41,32 @@ pub enum Screen {
     Loading,
     Title,
     Credits,
+    Settings,
+    Campaign,
+    Draft,
     Playing,
+    /// Passes straight back to [`Screen::Playing`], so a match can start over.
+    Restart,
+    Results,
+}
+
+/// Whether a match is running or stopped behind the pause menu.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Playing)]
+pub enum Pause {
+    #[default]
+    Running,
+    Paused,
+    /// The settings panel, opened from the pause menu.
+    Settings,
+}
+
+/// What the results screen shows.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Results)]
+pub enum ResultsView {
+    #[default]
+    Standings,
+    /// The settings panel, opened from the results.
+    Settings,
 }
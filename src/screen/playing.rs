@@ -1,31 +1,195 @@
-use bevy::prelude::*;
+//! The screen a match is played on, with a pause menu over it.
 
-use super::Screen;
-use crate::game::{
-    assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, spawn::level::SpawnBoard,
+use bevy::{ecs::system::EntityCommands, prelude::*, ui::FocusPolicy};
+use bevy_la_mesa::Card;
+
+use super::{settings::spawn_settings_panel, Pause, Screen};
+use crate::{
+    game::{
+        ai::AiPlayers,
+        assets::SoundtrackKey,
+        audio::soundtrack::PlaySoundtrack,
+        cards::{GameOver, GameRng, GameState, Kard},
+        input::{ActionState, FocusScope, GameAction, InputBindings},
+        placement::DraggedCard,
+        preview::HoveredCard,
+        settings::Settings,
+        spawn::level::SpawnBoard,
+        trade::{PendingTrade, TradeDraft},
+        tutorial::{Tutorial, TutorialMatch, TUTORIAL_SEED},
+    },
+    ui::{palette::PAUSE_OVERLAY, prelude::*},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Playing), enter_playing);
     app.add_systems(OnExit(Screen::Playing), exit_playing);
+    app.add_systems(OnEnter(Screen::Restart), restart_match);
+
+    app.add_systems(OnEnter(Pause::Running), resume_time);
+    app.add_systems(OnEnter(Pause::Paused), enter_pause_menu);
+    app.add_systems(OnEnter(Pause::Settings), enter_pause_settings);
 
-    // app.add_systems(
-    //     Update,
-    //     return_to_title_screen
-    //         .run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::Escape))),
-    // );
+    app.register_type::<PauseAction>();
+    app.add_systems(
+        Update,
+        (
+            toggle_pause.run_if(in_state(Screen::Playing)),
+            handle_pause_action.run_if(in_state(Pause::Paused)),
+        ),
+    );
 }
 
-fn enter_playing(mut commands: Commands) {
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    QuitToTitle,
+    Settings,
+    Concede,
+    /// Starts over with the same decks, scenario, opponents and seed.
+    Restart,
+}
+
+fn enter_playing(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    // Every match plays out from the start of its seed, so it can be replayed.
+    *rng = GameRng::new(rng.seed);
     commands.trigger(SpawnBoard);
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
 }
 
-fn exit_playing(mut commands: Commands) {
+/// Clears the table and the match state, so the next match starts fresh.
+fn exit_playing(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut state: ResMut<GameState>,
+    mut pending_trade: ResMut<PendingTrade>,
+    mut trade_draft: ResMut<TradeDraft>,
+    mut dragged_card: ResMut<DraggedCard>,
+    mut hovered_card: ResMut<HoveredCard>,
+    cards: Query<Entity, With<Card<Kard>>>,
+) {
     // We could use [`StateScoped`] on the sound playing entites instead.
     commands.trigger(PlaySoundtrack::Disable);
+    time.unpause();
+
+    // Cards are spawned by la_mesa, so they can't be state scoped.
+    for entity in &cards {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    state.reset();
+    pending_trade.0 = None;
+    *trade_draft = TradeDraft::default();
+    dragged_card.0 = None;
+    hovered_card.0 = None;
+}
+
+fn restart_match(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Playing);
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn toggle_pause(
+    actions: Res<ActionState>,
+    pause: Res<State<Pause>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    if !actions.just_pressed(GameAction::Pause) {
+        return;
+    }
+    next_pause.set(match pause.get() {
+        Pause::Running => Pause::Paused,
+        Pause::Paused => Pause::Running,
+        Pause::Settings => Pause::Paused,
+    });
+}
+
+/// A full-screen overlay that takes the clicks and the focus cursor away
+/// from the table.
+fn spawn_overlay<'a>(commands: &'a mut Commands, pause: Pause) -> EntityCommands<'a> {
+    let mut overlay = commands.ui_root();
+    overlay.insert((
+        Name::new("Pause Overlay"),
+        BackgroundColor(PAUSE_OVERLAY),
+        FocusPolicy::Block,
+        ZIndex::Global(20),
+        FocusScope,
+        StateScoped(pause),
+    ));
+    overlay
+}
+
+/// Stops the phase timer, the AI and every tween by pausing virtual time.
+fn enter_pause_menu(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+
+    spawn_overlay(&mut commands, Pause::Paused).with_children(|children| {
+        children.header("Paused");
+        children.button("Resume").insert(PauseAction::Resume);
+        children
+            .button("Quit to Title")
+            .insert(PauseAction::QuitToTitle);
+        children.button("Settings").insert(PauseAction::Settings);
+        children.button("Concede").insert(PauseAction::Concede);
+        children.button("Restart").insert(PauseAction::Restart);
+    });
+}
+
+fn enter_pause_settings(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    bindings: Res<InputBindings>,
+) {
+    spawn_overlay(&mut commands, Pause::Settings)
+        .with_children(|children| spawn_settings_panel(children, &settings, &bindings));
 }
 
-fn return_to_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::Title);
+fn handle_pause_action(
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut button_query: InteractionQuery<&PauseAction>,
+    mut ew_game_over: EventWriter<GameOver>,
+    mut state: ResMut<GameState>,
+    ai_players: Res<AiPlayers>,
+    tutorial_match: Option<Res<TutorialMatch>>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                PauseAction::Resume => next_pause.set(Pause::Running),
+                PauseAction::QuitToTitle => next_screen.set(Screen::Title),
+                PauseAction::Settings => next_pause.set(Pause::Settings),
+                PauseAction::Concede => {
+                    // The player at the table gives up, or the first human
+                    // while an AI is moving.
+                    let loser = if ai_players.0.contains(&state.player) {
+                        (1..=state.num_players())
+                            .find(|player| !ai_players.0.contains(player))
+                            .unwrap_or(state.player)
+                    } else {
+                        state.player
+                    };
+                    if let Some(event) = (1..=state.num_players())
+                        .filter(|player| *player != loser)
+                        .max_by_key(|player| state.get_balance(*player))
+                        .and_then(|player_won| state.finish(player_won))
+                    {
+                        ew_game_over.send(event);
+                    }
+                }
+                PauseAction::Restart => {
+                    if tutorial_match.is_some() {
+                        commands.insert_resource(Tutorial::default());
+                        commands.insert_resource(GameRng::new(TUTORIAL_SEED));
+                    }
+                    next_screen.set(Screen::Restart);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,333 @@
+//! The end-of-match screen: final standings, each player's bank over the
+//! match, the chips they produced and sold and the cards they played.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use super::{settings::spawn_settings_panel, ResultsView, Screen};
+use crate::{
+    game::{
+        assets::SoundtrackKey,
+        audio::soundtrack::PlaySoundtrack,
+        cards::{CardType, GameOver, GameRng, GameState},
+        input::InputBindings,
+        settings::Settings,
+        spawn::level::player_color,
+        tutorial::{Tutorial, TutorialMatch, TUTORIAL_SEED},
+    },
+    ui::{palette::*, prelude::*},
+};
+
+/// Height of the tallest bar in the bank chart.
+const CHART_HEIGHT: f32 = 120.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Results), enter_results);
+    app.add_systems(OnExit(Screen::Results), exit_results);
+    app.add_systems(OnEnter(ResultsView::Standings), show_standings);
+    app.add_systems(OnEnter(ResultsView::Settings), show_settings);
+
+    app.register_type::<ResultsAction>();
+    app.add_systems(
+        Update,
+        (
+            finish_match.run_if(in_state(Screen::Playing)),
+            handle_results_action.run_if(in_state(ResultsView::Standings)),
+        ),
+    );
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum ResultsAction {
+    /// The same decks, scenario and opponents, with a new seed.
+    Rematch,
+    /// The same match again, seed and all.
+    Replay,
+    ChangeSettings,
+    Title,
+}
+
+/// How the last match went, taken before the table is cleared.
+#[derive(Resource)]
+struct MatchResults {
+    player_won: usize,
+    turns: usize,
+    seed: u64,
+    players: Vec<PlayerResults>,
+}
+
+struct PlayerResults {
+    player: usize,
+    bank: i32,
+    bankrupt: bool,
+    bank_history: Vec<i32>,
+    income: i32,
+    expenses: i32,
+    chips_produced: usize,
+    chips_sold: usize,
+    card_usage: Vec<(CardType, usize)>,
+}
+
+impl MatchResults {
+    fn new(state: &GameState, player_won: usize, seed: u64) -> Self {
+        let players = (1..=state.num_players())
+            .map(|player| {
+                let totals: Vec<i32> = state
+                    .get_ledger_breakdown(player)
+                    .into_iter()
+                    .map(|(_, total)| total)
+                    .collect();
+                PlayerResults {
+                    player,
+                    bank: state.get_balance(player),
+                    bankrupt: state.is_bankrupt(player),
+                    bank_history: state.bank_history(player),
+                    income: totals.iter().filter(|total| **total > 0).sum(),
+                    expenses: -totals.iter().filter(|total| **total < 0).sum::<i32>(),
+                    chips_produced: state.chips_produced[player - 1],
+                    chips_sold: state.chips_sold(player),
+                    card_usage: state.card_usage(player),
+                }
+            })
+            .collect();
+        Self {
+            player_won,
+            turns: state.turn_number,
+            seed,
+            players,
+        }
+    }
+
+    /// The winner first, then everyone else from richest to poorest.
+    fn standings(&self) -> Vec<&PlayerResults> {
+        let mut standings: Vec<&PlayerResults> = self.players.iter().collect();
+        standings.sort_by_key(|results| (results.player != self.player_won, -results.bank));
+        standings
+    }
+}
+
+/// Leaves the table for the results once the match is decided.
+fn finish_match(
+    mut commands: Commands,
+    mut er_game_over: EventReader<GameOver>,
+    state: Res<GameState>,
+    rng: Res<GameRng>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(event) = er_game_over.read().last() else {
+        return;
+    };
+    commands.insert_resource(MatchResults::new(&state, event.player_won, rng.seed));
+    next_screen.set(Screen::Results);
+}
+
+fn enter_results(mut commands: Commands) {
+    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Title));
+}
+
+fn exit_results(mut commands: Commands) {
+    commands.remove_resource::<MatchResults>();
+    commands.trigger(PlaySoundtrack::Disable);
+}
+
+fn show_standings(mut commands: Commands, results: Res<MatchResults>, settings: Res<Settings>) {
+    let row = || NodeBundle {
+        style: Style {
+            column_gap: Px(10.0),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Results"),
+            BackgroundColor(HUD_PANEL_BACKGROUND),
+            StateScoped(ResultsView::Standings),
+        ))
+        .with_children(|children| {
+            children.header(format!("Player {} wins!", results.player_won));
+            children.label(format!(
+                "Decided on turn {}, seed {}",
+                results.turns, results.seed
+            ));
+
+            children
+                .spawn((Name::new("Standings"), row()))
+                .insert(Style {
+                    column_gap: Px(30.0),
+                    align_items: AlignItems::FlexStart,
+                    ..default()
+                })
+                .with_children(|children| {
+                    for (rank, player) in results.standings().into_iter().enumerate() {
+                        spawn_player_results(children, rank + 1, player, settings.colour_blind);
+                    }
+                });
+
+            children.label("Bank per turn");
+            spawn_bank_chart(children, &results, settings.colour_blind);
+
+            children.spawn(row()).with_children(|children| {
+                children.button("Rematch").insert(ResultsAction::Rematch);
+                children.button("Replay").insert(ResultsAction::Replay);
+                children
+                    .button("Change Settings")
+                    .insert(ResultsAction::ChangeSettings);
+                children.button("Title").insert(ResultsAction::Title);
+            });
+        });
+}
+
+/// Bank, chips and cards played of one player.
+fn spawn_player_results(
+    children: &mut ChildBuilder,
+    rank: usize,
+    results: &PlayerResults,
+    colour_blind: bool,
+) {
+    children
+        .spawn((
+            Name::new(format!("Results - Player {}", results.player)),
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Px(4.0),
+                    padding: UiRect::all(Px(6.0)),
+                    border: UiRect::top(Px(4.0)),
+                    ..default()
+                },
+                border_color: BorderColor(player_color(results.player, colour_blind)),
+                ..default()
+            },
+        ))
+        .with_children(|children| {
+            children.label(format!("{}. Player {}", rank, results.player));
+            children.label(if results.bankrupt {
+                format!("Bank: ${} (bankrupt)", results.bank)
+            } else {
+                format!("Bank: ${}", results.bank)
+            });
+            children.label(format!("Income: ${}", results.income));
+            children.label(format!("Expenses: ${}", results.expenses));
+            children.label(format!("Chips produced: {}", results.chips_produced));
+            children.label(format!("Chips sold: {}", results.chips_sold));
+            children.label("Cards played");
+            if results.card_usage.is_empty() {
+                children.label("None");
+            }
+            for (card_type, count) in &results.card_usage {
+                children.label(format!("{} x{}", card_type.name(), count));
+            }
+        });
+}
+
+/// A bar per player for every turn, in the players' colours.
+fn spawn_bank_chart(children: &mut ChildBuilder, results: &MatchResults, colour_blind: bool) {
+    let highest = results
+        .players
+        .iter()
+        .flat_map(|player| player.bank_history.iter().copied())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let turns = results
+        .players
+        .iter()
+        .map(|player| player.bank_history.len())
+        .max()
+        .unwrap_or(0);
+
+    children
+        .spawn((
+            Name::new("Bank Chart"),
+            NodeBundle {
+                style: Style {
+                    height: Px(CHART_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    column_gap: Px(6.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|children| {
+            for turn in 0..turns {
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            height: Percent(100.0),
+                            align_items: AlignItems::FlexEnd,
+                            column_gap: Px(1.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|children| {
+                        for player in &results.players {
+                            let balance = player.bank_history.get(turn).copied().unwrap_or(0);
+                            children.spawn(NodeBundle {
+                                style: Style {
+                                    width: Px(6.0),
+                                    height: Percent(balance.max(0) as f32 / highest as f32 * 100.0),
+                                    ..default()
+                                },
+                                background_color: BackgroundColor(player_color(
+                                    player.player,
+                                    colour_blind,
+                                )),
+                                ..default()
+                            });
+                        }
+                    });
+            }
+        });
+}
+
+fn show_settings(mut commands: Commands, settings: Res<Settings>, bindings: Res<InputBindings>) {
+    commands
+        .ui_root()
+        .insert((
+            BackgroundColor(HUD_PANEL_BACKGROUND),
+            StateScoped(ResultsView::Settings),
+        ))
+        .with_children(|children| spawn_settings_panel(children, &settings, &bindings));
+}
+
+fn handle_results_action(
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_view: ResMut<NextState<ResultsView>>,
+    mut button_query: InteractionQuery<&ResultsAction>,
+    tutorial_match: Option<Res<TutorialMatch>>,
+    results: Res<MatchResults>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                ResultsAction::Rematch => {
+                    // The tutorial is scripted around its own seed.
+                    if tutorial_match.is_some() {
+                        commands.insert_resource(Tutorial::default());
+                        commands.insert_resource(GameRng::new(TUTORIAL_SEED));
+                    } else {
+                        commands.insert_resource(GameRng::new(rand::random()));
+                    }
+                    next_screen.set(Screen::Playing);
+                }
+                ResultsAction::Replay => {
+                    if tutorial_match.is_some() {
+                        commands.insert_resource(Tutorial::default());
+                    }
+                    commands.insert_resource(GameRng::new(results.seed));
+                    next_screen.set(Screen::Playing);
+                }
+                ResultsAction::ChangeSettings => next_view.set(ResultsView::Settings),
+                ResultsAction::Title => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! A settings screen, reached from the title screen, the pause menu or the
+//! results, with audio, video and gameplay options and the keyboard and
+//! gamepad bindings. Changes are saved as soon as they are made.
+
+use bevy::{prelude::*, ui::RelativeCursorPosition, ui::Val::*};
+
+use super::{Pause, ResultsView, Screen};
+use crate::{
+    game::{
+        assets::SoundtrackKey,
+        audio::soundtrack::PlaySoundtrack,
+        input::{button_name, key_name, Device, GameAction, InputBindings, Rebinding},
+        settings::Settings,
+    },
+    ui::{palette::*, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Settings), enter_settings);
+    app.add_systems(OnExit(Screen::Settings), exit_settings);
+
+    app.add_systems(
+        Update,
+        (
+            capture_binding,
+            handle_settings_action,
+            drag_slider,
+            update_settings_widgets,
+        )
+            .chain()
+            .run_if(any_with_component::<SettingsAction>),
+    );
+    app.register_type::<SettingsAction>();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+enum Slider {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    AnimationSpeed,
+}
+
+impl Slider {
+    const ALL: [Slider; 4] = [
+        Slider::MasterVolume,
+        Slider::MusicVolume,
+        Slider::SfxVolume,
+        Slider::AnimationSpeed,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Slider::MasterVolume => "Master Volume",
+            Slider::MusicVolume => "Music Volume",
+            Slider::SfxVolume => "SFX Volume",
+            Slider::AnimationSpeed => "Animation Speed",
+        }
+    }
+
+    /// Lowest value, highest value and step.
+    fn range(&self) -> (f32, f32, f32) {
+        match self {
+            Slider::AnimationSpeed => (0.5, 2.0, 0.25),
+            _ => (0.0, 1.0, 0.05),
+        }
+    }
+
+    fn get(&self, settings: &Settings) -> f32 {
+        match self {
+            Slider::MasterVolume => settings.master_volume,
+            Slider::MusicVolume => settings.music_volume,
+            Slider::SfxVolume => settings.sfx_volume,
+            Slider::AnimationSpeed => settings.animation_speed,
+        }
+    }
+
+    fn field<'a>(&self, settings: &'a mut Settings) -> &'a mut f32 {
+        match self {
+            Slider::MasterVolume => &mut settings.master_volume,
+            Slider::MusicVolume => &mut settings.music_volume,
+            Slider::SfxVolume => &mut settings.sfx_volume,
+            Slider::AnimationSpeed => &mut settings.animation_speed,
+        }
+    }
+
+    /// Snaps `value` to a step and stores it, leaving `settings` untouched
+    /// if nothing changes.
+    fn set(&self, settings: &mut ResMut<Settings>, value: f32) {
+        let (min, max, step) = self.range();
+        let value = (((value - min) / step).round() * step + min).clamp(min, max);
+        if (self.get(settings) - value).abs() > f32::EPSILON {
+            *self.field(settings) = value;
+        }
+    }
+
+    fn fraction(&self, settings: &Settings) -> f32 {
+        let (min, max, _) = self.range();
+        (self.get(settings) - min) / (max - min)
+    }
+
+    fn text(&self, settings: &Settings) -> String {
+        match self {
+            Slider::AnimationSpeed => format!("{:.2}x", self.get(settings)),
+            _ => format!("{:.0}%", self.get(settings) * 100.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+enum Toggle {
+    Shadows,
+    ColourBlind,
+}
+
+impl Toggle {
+    const ALL: [Toggle; 2] = [Toggle::Shadows, Toggle::ColourBlind];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Toggle::Shadows => "Shadows",
+            Toggle::ColourBlind => "Colour-blind Palette",
+        }
+    }
+
+    fn field<'a>(&self, settings: &'a mut Settings) -> &'a mut bool {
+        match self {
+            Toggle::Shadows => &mut settings.shadows,
+            Toggle::ColourBlind => &mut settings.colour_blind,
+        }
+    }
+
+    fn text(&self, settings: &Settings) -> String {
+        let on = match self {
+            Toggle::Shadows => settings.shadows,
+            Toggle::ColourBlind => settings.colour_blind,
+        };
+        let text = if on { "On" } else { "Off" };
+        text.to_string()
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum SettingsAction {
+    Decrease(Slider),
+    Increase(Slider),
+    Toggle(Toggle),
+    Rebind(GameAction, Device),
+    ResetBindings,
+    Back,
+}
+
+/// Clickable track of a slider.
+#[derive(Component)]
+struct SliderBar(Slider);
+
+#[derive(Component)]
+struct SliderFill(Slider);
+
+#[derive(Component)]
+struct SliderValue(Slider);
+
+fn binding_text(bindings: &InputBindings, action: GameAction, device: Device) -> String {
+    let binding = bindings.get(action);
+    match device {
+        Device::Keyboard => key_name(binding.key),
+        Device::Gamepad => button_name(binding.button),
+    }
+}
+
+fn enter_settings(mut commands: Commands, settings: Res<Settings>, bindings: Res<InputBindings>) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Settings))
+        .with_children(|children| spawn_settings_panel(children, &settings, &bindings));
+
+    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Title));
+}
+
+/// Options and camera bindings beside the other bindings, with Reset
+/// Controls and Back below.
+pub(super) fn spawn_settings_panel(
+    children: &mut ChildBuilder,
+    settings: &Settings,
+    bindings: &InputBindings,
+) {
+    let row = || NodeBundle {
+        style: Style {
+            column_gap: Px(10.0),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+    let column = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(4.0),
+            ..default()
+        },
+        ..default()
+    };
+    let small_button = |width: f32| Style {
+        width: Px(width),
+        height: Px(26.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    // A row per action with its key and gamepad button, under a heading row.
+    let spawn_bindings = |children: &mut ChildBuilder, camera_moves: bool| {
+        children.spawn(row()).with_children(|children| {
+            children.label("");
+            children.label("Keyboard").insert(small_button(160.0));
+            children.label("Gamepad").insert(small_button(160.0));
+        });
+        for action in GameAction::ALL
+            .into_iter()
+            .filter(|action| action.is_camera_move() == camera_moves)
+        {
+            children.spawn(row()).with_children(|children| {
+                children.label(action.name());
+                for device in [Device::Keyboard, Device::Gamepad] {
+                    children
+                        .button(binding_text(bindings, action, device))
+                        .insert((SettingsAction::Rebind(action, device), small_button(160.0)));
+                }
+            });
+        }
+    };
+
+    children
+        .spawn((Name::new("Settings"), row()))
+        .insert(Style {
+            column_gap: Px(40.0),
+            align_items: AlignItems::FlexStart,
+            ..default()
+        })
+        .with_children(|children| {
+            children
+                .spawn((Name::new("Options"), column()))
+                .with_children(|children| {
+                    children.header("Options");
+                    for slider in Slider::ALL {
+                        children.spawn(row()).with_children(|children| {
+                            children.label(slider.name());
+                            children
+                                .button("-")
+                                .insert((SettingsAction::Decrease(slider), small_button(30.0)));
+                            children
+                                .spawn((
+                                    Name::new("Slider"),
+                                    ButtonBundle {
+                                        style: Style {
+                                            width: Px(160.0),
+                                            height: Px(12.0),
+                                            ..default()
+                                        },
+                                        background_color: BackgroundColor(HUD_PHASE_UPCOMING),
+                                        ..default()
+                                    },
+                                    SliderBar(slider),
+                                    RelativeCursorPosition::default(),
+                                ))
+                                .with_children(|children| {
+                                    children.spawn((
+                                        NodeBundle {
+                                            style: Style {
+                                                width: Percent(slider.fraction(settings) * 100.0),
+                                                height: Percent(100.0),
+                                                ..default()
+                                            },
+                                            background_color: BackgroundColor(NODE_BACKGROUND),
+                                            ..default()
+                                        },
+                                        SliderFill(slider),
+                                    ));
+                                });
+                            children
+                                .button("+")
+                                .insert((SettingsAction::Increase(slider), small_button(30.0)));
+                            children.spawn((
+                                TextBundle::from_section(
+                                    slider.text(settings),
+                                    TextStyle {
+                                        font_size: 14.0,
+                                        color: LABEL_TEXT,
+                                        ..default()
+                                    },
+                                )
+                                .with_style(Style {
+                                    width: Px(50.0),
+                                    ..default()
+                                }),
+                                SliderValue(slider),
+                            ));
+                        });
+                    }
+                    for toggle in Toggle::ALL {
+                        children.spawn(row()).with_children(|children| {
+                            children.label(toggle.name());
+                            children
+                                .button(toggle.text(settings))
+                                .insert((SettingsAction::Toggle(toggle), small_button(160.0)));
+                        });
+                    }
+                    children.header("Camera");
+                    spawn_bindings(children, true);
+                });
+
+            children
+                .spawn((Name::new("Controls"), column()))
+                .with_children(|children| {
+                    children.header("Controls");
+                    spawn_bindings(children, false);
+                    children.label("Pick a binding, then press the new key or button.");
+                });
+        });
+
+    children.spawn(row()).with_children(|children| {
+        children
+            .button("Reset Controls")
+            .insert(SettingsAction::ResetBindings);
+        children.button("Back").insert(SettingsAction::Back);
+    });
+}
+
+fn exit_settings(mut commands: Commands, mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+    commands.trigger(PlaySoundtrack::Disable);
+}
+
+/// Takes the next key or gamepad button for the binding being changed.
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+) {
+    let Some((action, device)) = rebinding.0 else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.0 = None;
+        return;
+    }
+
+    match device {
+        Device::Keyboard => {
+            if let Some(key) = keys.get_just_pressed().next() {
+                bindings.set_key(action, *key);
+                rebinding.0 = None;
+            }
+        }
+        Device::Gamepad => {
+            if let Some(button) = gamepad_buttons.get_just_pressed().next() {
+                bindings.set_button(action, button.button_type);
+                rebinding.0 = None;
+            }
+        }
+    }
+}
+
+fn handle_settings_action(
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_results_view: ResMut<NextState<ResultsView>>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    mut button_query: InteractionQuery<&SettingsAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                SettingsAction::Decrease(slider) => {
+                    let value = slider.get(&settings) - slider.range().2;
+                    slider.set(&mut settings, value);
+                }
+                SettingsAction::Increase(slider) => {
+                    let value = slider.get(&settings) + slider.range().2;
+                    slider.set(&mut settings, value);
+                }
+                SettingsAction::Toggle(toggle) => {
+                    let field = toggle.field(&mut settings);
+                    *field = !*field;
+                }
+                SettingsAction::Rebind(action, device) => rebinding.0 = Some((*action, *device)),
+                SettingsAction::ResetBindings => {
+                    *bindings = InputBindings::default();
+                    rebinding.0 = None;
+                }
+                SettingsAction::Back => {
+                    rebinding.0 = None;
+                    match screen.get() {
+                        Screen::Settings => next_screen.set(Screen::Title),
+                        Screen::Playing => next_pause.set(Pause::Paused),
+                        Screen::Results => next_results_view.set(ResultsView::Standings),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn drag_slider(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut settings: ResMut<Settings>,
+    bars: Query<(&SliderBar, &Interaction, &RelativeCursorPosition)>,
+) {
+    if !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    for (bar, interaction, cursor) in &bars {
+        let Some(cursor) = cursor.normalized else {
+            continue;
+        };
+        if *interaction == Interaction::Pressed {
+            let (min, max, _) = bar.0.range();
+            bar.0
+                .set(&mut settings, min + cursor.x.clamp(0.0, 1.0) * (max - min));
+        }
+    }
+}
+
+fn update_settings_widgets(
+    settings: Res<Settings>,
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+    buttons: Query<(&SettingsAction, &Children)>,
+    mut fills: Query<(&SliderFill, &mut Style)>,
+    mut values: Query<(&SliderValue, &mut Text)>,
+    mut texts: Query<&mut Text, Without<SliderValue>>,
+) {
+    if !settings.is_changed() && !bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    for (fill, mut style) in &mut fills {
+        style.width = Percent(fill.0.fraction(&settings) * 100.0);
+    }
+    for (value, mut text) in &mut values {
+        text.sections[0].value = value.0.text(&settings);
+    }
+
+    for (settings_action, children) in &buttons {
+        let value = match *settings_action {
+            SettingsAction::Toggle(toggle) => toggle.text(&settings),
+            SettingsAction::Rebind(action, device) if rebinding.0 == Some((action, device)) => {
+                match device {
+                    Device::Keyboard => "Press a key...".to_string(),
+                    Device::Gamepad => "Press a button...".to_string(),
+                }
+            }
+            SettingsAction::Rebind(action, device) => binding_text(&bindings, action, device),
+            _ => continue,
+        };
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value.clone_from(&value);
+        }
+    }
+}
@@ -7,22 +7,42 @@ use bevy::{
 
 use super::Screen;
 use crate::{
-    game::{assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack},
+    game::{
+        ai::AiPlayers,
+        assets::SoundtrackKey,
+        audio::soundtrack::PlaySoundtrack,
+        campaign::ActiveScenario,
+        cards::GameRng,
+        draft::DraftedDecks,
+        territory::TerritorySettings,
+        tutorial::{Tutorial, TutorialMatch, TUTORIAL_SEED},
+    },
     ui::prelude::*,
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Title), enter_title);
+    app.add_systems(OnEnter(Screen::Title), (enter_title, reset_match_setup));
     app.add_systems(OnExit(Screen::Title), exit_title);
 
     app.register_type::<TitleAction>();
-    app.add_systems(Update, handle_title_action.run_if(in_state(Screen::Title)));
+    app.add_systems(
+        Update,
+        (handle_title_action, update_territory_button)
+            .chain()
+            .run_if(in_state(Screen::Title)),
+    );
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
 enum TitleAction {
     Play,
+    Draft,
+    Campaign,
+    Tutorial,
+    /// Turns the territory map expansion on or off for the next match.
+    Territory,
+    Settings,
     Credits,
     /// Exit doesn't work well with embedded applications.
     #[cfg(not(target_family = "wasm"))]
@@ -31,7 +51,19 @@ enum TitleAction {
 
 const TITLE_BACKGROUND_COLOR: Color = Color::srgb(0.0, 0.0, 239.0);
 
-fn enter_title(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn territory_text(territory_settings: &TerritorySettings) -> String {
+    if territory_settings.enabled {
+        "Territory: On".to_string()
+    } else {
+        "Territory: Off".to_string()
+    }
+}
+
+fn enter_title(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    territory_settings: Res<TerritorySettings>,
+) {
     commands
         .ui_root()
         .insert((
@@ -79,6 +111,13 @@ fn enter_title(mut commands: Commands, asset_server: Res<AssetServer>) {
         .insert(StateScoped(Screen::Title))
         .with_children(|children| {
             children.button("Play").insert(TitleAction::Play);
+            children.button("Draft").insert(TitleAction::Draft);
+            children.button("Campaign").insert(TitleAction::Campaign);
+            children.button("Tutorial").insert(TitleAction::Tutorial);
+            children
+                .button(territory_text(&territory_settings))
+                .insert(TitleAction::Territory);
+            children.button("Settings").insert(TitleAction::Settings);
             children.button("Credits").insert(TitleAction::Credits);
 
             #[cfg(not(target_family = "wasm"))]
@@ -89,14 +128,28 @@ fn enter_title(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn handle_title_action(
+    mut commands: Commands,
     mut next_screen: ResMut<NextState<Screen>>,
     mut button_query: InteractionQuery<&TitleAction>,
+    mut territory_settings: ResMut<TerritorySettings>,
     #[cfg(not(target_family = "wasm"))] mut app_exit: EventWriter<AppExit>,
 ) {
     for (interaction, action) in &mut button_query {
         if matches!(interaction, Interaction::Pressed) {
             match action {
                 TitleAction::Play => next_screen.set(Screen::Playing),
+                TitleAction::Draft => next_screen.set(Screen::Draft),
+                TitleAction::Campaign => next_screen.set(Screen::Campaign),
+                TitleAction::Tutorial => {
+                    commands.insert_resource(Tutorial::default());
+                    commands.insert_resource(TutorialMatch);
+                    commands.insert_resource(GameRng::new(TUTORIAL_SEED));
+                    next_screen.set(Screen::Playing);
+                }
+                TitleAction::Territory => {
+                    territory_settings.enabled = !territory_settings.enabled;
+                }
+                TitleAction::Settings => next_screen.set(Screen::Settings),
                 TitleAction::Credits => next_screen.set(Screen::Credits),
 
                 #[cfg(not(target_family = "wasm"))]
@@ -108,6 +161,37 @@ fn handle_title_action(
     }
 }
 
+fn update_territory_button(
+    territory_settings: Res<TerritorySettings>,
+    button_query: Query<(&TitleAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !territory_settings.is_changed() {
+        return;
+    }
+    for (action, children) in &button_query {
+        if *action != TitleAction::Territory {
+            continue;
+        }
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = territory_text(&territory_settings);
+            }
+        }
+    }
+}
+
+/// Forgets how the last match was set up, so Play starts a standard match
+/// with a new seed.
+fn reset_match_setup(mut commands: Commands, mut ai_players: ResMut<AiPlayers>) {
+    commands.insert_resource(GameRng::new(rand::random()));
+    commands.remove_resource::<ActiveScenario>();
+    commands.remove_resource::<Tutorial>();
+    commands.remove_resource::<TutorialMatch>();
+    commands.remove_resource::<DraftedDecks>();
+    ai_players.0.clear();
+}
+
 fn exit_title(mut commands: Commands) {
     // We could use [`StateScoped`] on the sound playing entites instead.
     commands.trigger(PlaySoundtrack::Disable);
@@ -8,3 +8,34 @@ pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
 pub const NODE_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
+
+pub const HUD_PANEL_BACKGROUND: Color = Color::srgba(0.05, 0.05, 0.08, 0.85);
+pub const PAUSE_OVERLAY: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+pub const HUD_ACTIVE_BORDER: Color = Color::srgb(0.867, 0.827, 0.412);
+pub const HUD_PHASE_CURRENT: Color = Color::srgb(0.867, 0.827, 0.412);
+pub const HUD_PHASE_DONE: Color = Color::srgb(0.2, 0.3, 0.45);
+pub const HUD_PHASE_UPCOMING: Color = Color::srgb(0.12, 0.12, 0.15);
+pub const HUD_CARD_PLAYABLE: Color = Color::srgb(0.1, 0.55, 0.2);
+pub const HUD_CARD_UNAFFORDABLE: Color = Color::srgb(0.7, 0.1, 0.1);
+pub const HUD_CARD_BLOCKED: Color = Color::srgb(0.35, 0.35, 0.35);
+pub const HUD_CARD_PLAYABLE_COLOUR_BLIND: Color = Color::srgb(0.0, 0.447, 0.698);
+pub const HUD_CARD_UNAFFORDABLE_COLOUR_BLIND: Color = Color::srgb(0.835, 0.369, 0.0);
+pub const FOCUS_OUTLINE: Color = Color::srgb(0.867, 0.827, 0.412);
+
+/// Good news, such as a playable card or a payout.
+pub fn hud_positive(colour_blind: bool) -> Color {
+    if colour_blind {
+        HUD_CARD_PLAYABLE_COLOUR_BLIND
+    } else {
+        HUD_CARD_PLAYABLE
+    }
+}
+
+/// Bad news, such as a card the player can't afford.
+pub fn hud_negative(colour_blind: bool) -> Color {
+    if colour_blind {
+        HUD_CARD_UNAFFORDABLE_COLOUR_BLIND
+    } else {
+        HUD_CARD_UNAFFORDABLE
+    }
+}